@@ -0,0 +1,105 @@
+//! Compares the spatial grid broadphase against the brute-force pair loop.
+//!
+//! Run with `cargo bench --bench spatial_grid`.
+
+use std::{hint::black_box, time::Instant};
+
+use bevy::prelude::*;
+
+#[path = "../src/spatial.rs"]
+#[allow(dead_code)]
+mod spatial;
+
+use spatial::SpatialGrid;
+
+const RADIUS: f32 = 16.;
+const ITERATIONS: u32 = 10;
+
+fn main() {
+    for count in [500, 1_000, 2_000, 5_000, 10_000] {
+        let colliders = colliders(count);
+        assert_eq!(
+            grid_pairs(&colliders),
+            brute_force_pairs(&colliders),
+            "the grid misses or double counts pairs among {count} colliders",
+        );
+
+        let brute = time(|| brute_force_pairs(&colliders));
+        let grid = time(|| grid_pairs(&colliders));
+
+        println!(
+            "{count:>6} colliders: brute force {:>9.3} ms, grid {:>7.3} ms",
+            brute * 1000.,
+            grid * 1000.,
+        );
+    }
+}
+
+/// Deterministic spread of colliders over a world roughly the size of a sector.
+fn colliders(count: usize) -> Vec<(Entity, Vec2)> {
+    let mut state = 0x2545_f491_u32;
+    let mut next = move || {
+        state ^= state << 13;
+        state ^= state >> 17;
+        state ^= state << 5;
+        state as f32 / u32::MAX as f32
+    };
+
+    let side = (count as f32).sqrt() * RADIUS * 4.;
+
+    (0..count)
+        .map(|i| {
+            let pos = Vec2::new(next(), next()) * side - side / 2.;
+            (Entity::from_raw(i as u32), pos)
+        })
+        .collect()
+}
+
+fn time(mut f: impl FnMut() -> usize) -> f64 {
+    let start = Instant::now();
+    for _ in 0..ITERATIONS {
+        black_box(f());
+    }
+    start.elapsed().as_secs_f64() / ITERATIONS as f64
+}
+
+fn brute_force_pairs(colliders: &[(Entity, Vec2)]) -> usize {
+    let threshold = (RADIUS * 2.) * (RADIUS * 2.);
+    let mut hits = 0;
+
+    for (i, (_, a)) in colliders.iter().enumerate() {
+        for (_, b) in &colliders[i + 1..] {
+            if a.distance_squared(*b) <= threshold {
+                hits += 1;
+            }
+        }
+    }
+
+    hits
+}
+
+fn grid_pairs(colliders: &[(Entity, Vec2)]) -> usize {
+    let threshold = (RADIUS * 2.) * (RADIUS * 2.);
+    let mut grid = SpatialGrid::default();
+
+    for &(entity, pos) in colliders {
+        grid.insert(entity, pos - RADIUS, pos + RADIUS);
+    }
+
+    let mut hits = 0;
+
+    for &(entity, a) in colliders {
+        for other in grid.query(a - RADIUS, a + RADIUS) {
+            if other <= entity {
+                continue;
+            }
+
+            let b = colliders[other.index() as usize].1;
+            if a.distance_squared(b) <= threshold {
+                hits += 1;
+            }
+        }
+    }
+
+    hits
+}
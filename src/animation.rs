@@ -17,21 +17,25 @@ pub struct Animation {
     pub timer: Timer,
 }
 
+/// Flips the sprite horizontally to face the direction of its target velocity.
+#[derive(Component, Default, Debug)]
+pub struct FlipWithVelocity;
+
 pub struct AnimationPlugin;
 
 impl Plugin for AnimationPlugin {
     fn build(&self, app: &mut App) {
         app.add_systems(
             Update,
-            (animation_system).run_if(in_state(GameState::Running)),
+            (animation_system, flip_system).run_if(in_state(GameState::Running)),
         );
     }
 }
 
-fn animation_system(time: Res<Time>, mut object: Query<(&mut Animation, &mut Sprite, &Velocity)>) {
-    if let Ok((mut anim, mut sprite, vel)) = object.single_mut() {
-        if vel.target.x.abs() != 0. {
-            sprite.flip_x = vel.target.x < 0.;
+fn animation_system(time: Res<Time>, mut query: Query<(&mut Animation, &mut Sprite)>) {
+    for (mut anim, mut sprite) in query.iter_mut() {
+        if anim.first == anim.last {
+            continue;
         }
 
         anim.timer.tick(time.delta());
@@ -41,13 +45,15 @@ fn animation_system(time: Res<Time>, mut object: Query<(&mut Animation, &mut Spr
                 match anim.dir {
                     AnimationDir::Forwards => {
                         atlas.index += 1;
-                        if atlas.index == anim.last {
+                        if atlas.index >= anim.last {
+                            atlas.index = anim.last;
                             anim.dir = AnimationDir::Backwards;
                         }
                     }
                     AnimationDir::Backwards => {
-                        atlas.index -= 1;
-                        if atlas.index == anim.first {
+                        atlas.index = atlas.index.saturating_sub(1);
+                        if atlas.index <= anim.first {
+                            atlas.index = anim.first;
                             anim.dir = AnimationDir::Forwards;
                         }
                     }
@@ -56,3 +62,11 @@ fn animation_system(time: Res<Time>, mut object: Query<(&mut Animation, &mut Spr
         }
     }
 }
+
+fn flip_system(mut query: Query<(&mut Sprite, &Velocity), With<FlipWithVelocity>>) {
+    for (mut sprite, vel) in query.iter_mut() {
+        if vel.target.x.abs() != 0. {
+            sprite.flip_x = vel.target.x < 0.;
+        }
+    }
+}
@@ -1,9 +1,124 @@
-use bevy::prelude::*;
+use std::path::{Path, PathBuf};
+
+use bevy::{asset::AssetPath, prelude::*};
 use clap::Parser;
 
+use crate::config::{ConfigOverride, Format};
+
+/// Relative to the assets directory.
+pub const DEFAULT_CONFIG_PATH: &str = "config/game.config.json";
+/// Asset source serving the directory of the `--config` file.
+#[cfg(not(target_arch = "wasm32"))]
+pub const CONFIG_SOURCE: &str = "config-arg";
+
 #[derive(Parser, Resource, Debug, Clone)]
 pub struct Args {
     /// runs the game in debug mode
     #[clap(long, alias = "dbg")]
     pub debug: bool,
+
+    /// plays back a recorded run instead of reading input
+    #[clap(long, value_name = "FILE")]
+    pub replay: Option<PathBuf>,
+
+    /// loads this config file instead of the default one
+    #[clap(long, value_name = "FILE", value_parser = config_path)]
+    pub config: Option<PathBuf>,
+
+    /// overrides a config field, can be repeated
+    #[clap(long = "set", value_name = "KEY=VALUE")]
+    pub overrides: Vec<ConfigOverride>,
+
+    /// converts a data asset between JSON and RON, then exits without starting the game
+    #[clap(long, num_args = 2, value_names = ["IN", "OUT"])]
+    pub convert: Option<Vec<PathBuf>>,
+}
+
+impl Args {
+    /// Command-line arguments natively, URL query parameters on the web.
+    pub fn from_env() -> Self {
+        #[cfg(not(target_arch = "wasm32"))]
+        let args = Self::parse();
+        #[cfg(target_arch = "wasm32")]
+        let args = Self::from_query();
+
+        args
+    }
+
+    /// The `--config` file, read through [`CONFIG_SOURCE`], or the default
+    /// config under `assets/`. On the web the query's path is relative to the
+    /// assets directory.
+    pub fn config_asset_path(&self) -> AssetPath<'static> {
+        match &self.config {
+            #[cfg(not(target_arch = "wasm32"))]
+            Some(path) => AssetPath::from_path(Path::new(path.file_name().unwrap_or_default()))
+                .into_owned()
+                .with_source(CONFIG_SOURCE),
+            #[cfg(target_arch = "wasm32")]
+            Some(path) => AssetPath::from_path(path).into_owned(),
+            None => AssetPath::from(DEFAULT_CONFIG_PATH),
+        }
+    }
+
+    /// Where the tuning panel saves the config.
+    pub fn config_file(&self) -> PathBuf {
+        self.config
+            .clone()
+            .unwrap_or_else(|| Path::new("assets").join(DEFAULT_CONFIG_PATH))
+    }
+
+    /// `?debug&config=PATH&gravity=-30`: every parameter named after a config
+    /// field is an override.
+    #[cfg(target_arch = "wasm32")]
+    fn from_query() -> Self {
+        use bevy::reflect::Struct;
+
+        use crate::config::GameConfig;
+
+        let mut args = Self::parse_from(["hacker-pigeon"]);
+
+        let Some(params) = web_sys::window()
+            .and_then(|window| window.location().search().ok())
+            .and_then(|search| web_sys::UrlSearchParams::new_with_str(&search).ok())
+        else {
+            return args;
+        };
+
+        args.debug = params.has("debug");
+        if let Some(config) = params.get("config") {
+            match config_path(&config) {
+                Ok(config) => args.config = Some(config),
+                Err(err) => log::warn!("Ignoring query parameter config: {err}"),
+            }
+        }
+
+        let fields = GameConfig::default();
+        for key in (0..fields.field_len()).filter_map(|i| fields.name_at(i)) {
+            let Some(value) = params.get(key) else {
+                continue;
+            };
+
+            match format!("{key}={value}").parse() {
+                Ok(config_override) => args.overrides.push(config_override),
+                Err(err) => log::warn!("Ignoring query parameter {key}: {err}"),
+            }
+        }
+
+        args
+    }
+}
+
+/// Accepts a JSON or RON file anywhere on disk. It is made absolute so its
+/// directory can be served as an asset source.
+fn config_path(path: &str) -> Result<PathBuf, String> {
+    if Format::from_path(Path::new(path)).is_none() {
+        return Err(format!("`{path}` is not a .json or .ron file"));
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    let path = std::fs::canonicalize(path).map_err(|err| format!("can't read `{path}`: {err}"))?;
+    #[cfg(target_arch = "wasm32")]
+    let path = PathBuf::from(path);
+
+    Ok(path)
 }
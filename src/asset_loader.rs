@@ -1,10 +1,13 @@
 use bevy::prelude::*;
 use bevy_asset_loader::prelude::*;
-use bevy_common_assets::json::JsonAssetPlugin;
 
 use crate::{
-    config::{Config, GameConfig},
+    args::Args,
+    config::{Config, ConfigAssetPlugin, ConfigHandle, ConfigReloadPlugin, GameConfig},
+    difficulty::{DifficultyPresets, Presets},
     game_state::GameState,
+    level::LevelData,
+    replay::is_playing_back,
 };
 
 #[derive(AssetCollection, Resource)]
@@ -29,6 +32,10 @@ pub struct ImageAssets {
     pub enemy_drone_layout: Handle<TextureAtlasLayout>,
     #[asset(path = "enemies/drone_ball.png")]
     pub enemy_drone: Handle<Image>,
+    #[asset(texture_atlas_layout(tile_size_x = 32, tile_size_y = 32, columns = 4, rows = 5))]
+    pub enemy_drone_parts_layout: Handle<TextureAtlasLayout>,
+    #[asset(path = "enemies/drone.png")]
+    pub enemy_drone_parts: Handle<Image>,
 }
 
 #[derive(AssetCollection, Resource)]
@@ -47,46 +54,190 @@ pub struct AudioAssets {
     pub boom: Handle<AudioSource>,
 
     #[asset(path = "audio/jsfxr/player/hit.wav")]
-    pub _player_hit: Handle<AudioSource>,
+    pub player_hit: Handle<AudioSource>,
     #[asset(path = "audio/jsfxr/player/death.wav")]
-    pub _player_death: Handle<AudioSource>,
+    pub player_death: Handle<AudioSource>,
 
     #[asset(path = "audio/jsfxr/enemy/hit.wav")]
     pub _enemy_hit: Handle<AudioSource>,
     #[asset(path = "audio/jsfxr/enemy/death.wav")]
-    pub _enemy_death: Handle<AudioSource>,
+    pub enemy_death: Handle<AudioSource>,
+}
+
+#[derive(AssetCollection, Resource)]
+pub struct LevelAssets {
+    #[asset(path = "levels/city.level.json")]
+    pub city: Handle<LevelData>,
 }
 
 pub struct AssetLoaderPlugin;
 
 impl Plugin for AssetLoaderPlugin {
     fn build(&self, app: &mut App) {
-        app.add_plugins(JsonAssetPlugin::<GameConfig>::new(&["config.json"]))
-            .add_loading_state(
-                LoadingState::new(GameState::AssetLoading)
-                    .load_collection::<ImageAssets>()
-                    .load_collection::<AudioAssets>()
-                    .continue_to_state(GameState::Setup),
-            )
-            .add_systems(OnExit(GameState::Setup), load_config_resource);
+        app.add_plugins((
+            ConfigAssetPlugin::<GameConfig>::new(&["config.json", "config.ron"]),
+            ConfigAssetPlugin::<DifficultyPresets>::new(&["presets.json", "presets.ron"]),
+            ConfigAssetPlugin::<LevelData>::new(&["level.json", "level.ron"]),
+            ConfigReloadPlugin::<Config>::default(),
+            ConfigReloadPlugin::<Presets>::default(),
+        ))
+        .add_loading_state(
+            LoadingState::new(GameState::AssetLoading)
+                .load_collection::<ImageAssets>()
+                .load_collection::<AudioAssets>()
+                .load_collection::<LevelAssets>()
+                .continue_to_state(GameState::Setup),
+        )
+        .add_systems(Startup, start_config_load)
+        .add_systems(
+            OnExit(GameState::Setup),
+            (load_config_resource, load_presets_resource),
+        )
+        .add_systems(
+            PreUpdate,
+            apply_fixed_tick_rate
+                .run_if(resource_exists_and_changed::<Config>.and(not(is_playing_back))),
+        );
     }
 }
 
+/// Serves the directory of the `--config` file as [`CONFIG_SOURCE`], which
+/// also hot reloads it. Asset sources have to exist before `AssetPlugin`.
+#[cfg(not(target_arch = "wasm32"))]
+pub fn register_config_source(app: &mut App, args: &Args) {
+    use std::time::Duration;
+
+    use bevy::asset::io::AssetSource;
+
+    use crate::args::CONFIG_SOURCE;
+
+    let Some(dir) = args.config.as_deref().and_then(std::path::Path::parent) else {
+        return;
+    };
+    let dir = dir.to_string_lossy().into_owned();
+
+    app.register_asset_source(
+        CONFIG_SOURCE,
+        AssetSource::build()
+            .with_reader(AssetSource::get_default_reader(dir.clone()))
+            .with_watcher(AssetSource::get_default_watcher(
+                dir,
+                Duration::from_millis(300),
+            )),
+    );
+}
+
+/// Loads alongside the asset collections so configs are usually ready by `Setup`.
+fn start_config_load(mut commands: Commands, asset_server: Res<AssetServer>, args: Res<Args>) {
+    commands.insert_resource(ConfigHandle::<GameConfig>(
+        asset_server.load(args.config_asset_path()),
+    ));
+    commands.insert_resource(ConfigHandle::<DifficultyPresets>(
+        asset_server.load("config/difficulty.presets.json"),
+    ));
+}
+
 fn load_config_resource(
     mut commands: Commands,
     asset_server: Res<AssetServer>,
+    handle: Res<ConfigHandle<GameConfig>>,
     game_configs: Res<Assets<GameConfig>>,
 ) {
-    let game_config = GameConfigHandle(asset_server.load("config/game.config.json"));
+    let game_config = loaded_or_default(&asset_server, &handle, &game_configs);
+
+    commands.insert_resource(Config::new(game_config));
+}
+
+fn load_presets_resource(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    handle: Res<ConfigHandle<DifficultyPresets>>,
+    presets: Res<Assets<DifficultyPresets>>,
+) {
+    let presets = loaded_or_default(&asset_server, &handle, &presets);
+
+    commands.insert_resource(Presets { presets });
+}
+
+fn loaded_or_default<A: Asset + Clone + Default>(
+    asset_server: &AssetServer,
+    handle: &ConfigHandle<A>,
+    assets: &Assets<A>,
+) -> A {
+    if let Some(asset) = assets.get(handle.0.id()) {
+        return asset.clone();
+    }
 
-    let game_config = if let Some(game) = game_configs.get(game_config.0.id()) {
-        game.clone()
+    let path = handle.0.path().map(ToString::to_string).unwrap_or_default();
+
+    // the loader has already logged why it failed
+    if asset_server.load_state(handle.0.id()).is_failed() {
+        log::error!("{path} failed to load, using the defaults");
     } else {
-        GameConfig::default()
-    };
+        log::warn!("{path} is still loading, using the defaults until it is ready");
+    }
+
+    A::default()
+}
 
-    commands.insert_resource(Config { game: game_config });
+/// Replays pin their own tick rate, so this only follows the config otherwise.
+fn apply_fixed_tick_rate(config: Res<Config>, mut fixed_time: ResMut<Time<Fixed>>) {
+    fixed_time.set_timestep_hz(config.game.fixed_tick_rate);
 }
 
-#[derive(Resource)]
-struct GameConfigHandle(Handle<GameConfig>);
+#[cfg(test)]
+mod tests {
+    use std::time::{Duration, Instant};
+
+    use bevy::asset::LoadState;
+    use clap::Parser;
+
+    use super::*;
+
+    #[test]
+    fn config_arg_loads_from_anywhere_on_disk() {
+        let dir = std::env::temp_dir().join(format!("config-arg-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let file = dir.join("ci.json");
+        std::fs::write(&file, r#"{ "gravity": -33.0 }"#).unwrap();
+
+        let args =
+            Args::try_parse_from(["hacker-pigeon", "--config", file.to_str().unwrap()]).unwrap();
+
+        let mut app = App::new();
+        register_config_source(&mut app, &args);
+        app.add_plugins((
+            MinimalPlugins,
+            AssetPlugin::default(),
+            ConfigAssetPlugin::<GameConfig>::new(&["config.json", "config.ron"]),
+        ));
+
+        let handle: Handle<GameConfig> = app
+            .world()
+            .resource::<AssetServer>()
+            .load(args.config_asset_path());
+
+        let start = Instant::now();
+        while app
+            .world()
+            .resource::<Assets<GameConfig>>()
+            .get(&handle)
+            .is_none()
+        {
+            let state = app.world().resource::<AssetServer>().load_state(&handle);
+            assert!(!matches!(state, LoadState::Failed(_)), "{state:?}");
+            assert!(start.elapsed() < Duration::from_secs(10), "still {state:?}");
+            app.update();
+        }
+
+        let config = app.world().resource::<Assets<GameConfig>>().get(&handle);
+        assert_eq!(config.unwrap().gravity, -33.0);
+        std::fs::remove_dir_all(dir).unwrap();
+
+        let err = Args::try_parse_from(["hacker-pigeon", "--config", "ci.txt"]).unwrap_err();
+        assert!(
+            err.to_string().contains("not a .json or .ron file"),
+            "{err}"
+        );
+    }
+}
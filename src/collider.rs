@@ -0,0 +1,513 @@
+use bevy::prelude::*;
+
+/// Collision shape, centered on the entity's translation.
+///
+/// Rotations are in radians, counter-clockwise. Capsules run along their local
+/// Y axis with `half_length` measured between the two end-cap centers.
+#[derive(Component, Clone, Copy, Debug, PartialEq)]
+pub enum Collider {
+    Circle {
+        radius: f32,
+    },
+    Aabb {
+        half_size: Vec2,
+    },
+    Obb {
+        half_size: Vec2,
+        rotation: f32,
+    },
+    Capsule {
+        half_length: f32,
+        radius: f32,
+        rotation: f32,
+    },
+}
+
+impl Default for Collider {
+    fn default() -> Self {
+        Self::circle(0.)
+    }
+}
+
+impl Collider {
+    pub fn circle(radius: f32) -> Self {
+        Self::Circle { radius }
+    }
+
+    pub fn aabb(half_size: Vec2) -> Self {
+        Self::Aabb { half_size }
+    }
+
+    pub fn obb(half_size: Vec2, rotation: f32) -> Self {
+        Self::Obb {
+            half_size,
+            rotation,
+        }
+    }
+
+    pub fn capsule(half_length: f32, radius: f32, rotation: f32) -> Self {
+        Self::Capsule {
+            half_length,
+            radius,
+            rotation,
+        }
+    }
+
+    /// Radius of the smallest circle around the center containing the shape.
+    pub fn bounding_radius(&self) -> f32 {
+        match *self {
+            Self::Circle { radius } => radius,
+            Self::Aabb { half_size } | Self::Obb { half_size, .. } => half_size.length(),
+            Self::Capsule {
+                half_length,
+                radius,
+                ..
+            } => half_length + radius,
+        }
+    }
+
+    /// Whether this collider at `pos` overlaps `other` at `other_pos`.
+    pub fn intersects(&self, pos: Vec2, other: &Collider, other_pos: Vec2) -> bool {
+        let (core, radius) = self.core(pos);
+        let (other_core, other_radius) = other.core(other_pos);
+
+        let reach = radius + other_radius;
+
+        match (core, other_core) {
+            (Core::Box(a), Core::Box(b)) => boxes_overlap(&a, &b),
+            (a, b) => distance_squared(&a, &b) <= reach * reach,
+        }
+    }
+
+    /// Time of impact in `[0, 1]` of a circle moving from `start` to `end`
+    /// against this collider resting at `pos`.
+    pub fn sweep_circle(&self, pos: Vec2, start: Vec2, end: Vec2, radius: f32) -> Option<f32> {
+        match *self {
+            Self::Circle { radius: own } => {
+                sweep_point_circle(start, end - start, pos, own + radius)
+            }
+            Self::Aabb { half_size } => sweep_circle_obb(start, end, radius, pos, half_size, 0.),
+            Self::Obb {
+                half_size,
+                rotation,
+            } => sweep_circle_obb(start, end, radius, pos, half_size, rotation),
+            Self::Capsule {
+                half_length,
+                radius: own,
+                rotation,
+            } => {
+                let axis = Vec2::from_angle(rotation).rotate(Vec2::Y) * half_length;
+                let reach = own + radius;
+                let delta = end - start;
+
+                [
+                    sweep_point_circle(start, delta, pos + axis, reach),
+                    sweep_point_circle(start, delta, pos - axis, reach),
+                    sweep_circle_obb(start, end, 0., pos, Vec2::new(reach, half_length), rotation),
+                ]
+                .into_iter()
+                .flatten()
+                .min_by(f32::total_cmp)
+            }
+        }
+    }
+
+    /// Direction from the collider at `pos` towards `point`, away from its surface.
+    pub fn normal_at(&self, pos: Vec2, point: Vec2) -> Vec2 {
+        let closest = match self.core(pos).0 {
+            Core::Point(p) => p,
+            Core::Segment(a, b) => closest_point_on_segment(point, a, b),
+            Core::Box(obb) => obb.closest_point(point),
+        };
+
+        (point - closest)
+            .try_normalize()
+            .or_else(|| (point - pos).try_normalize())
+            .unwrap_or(Vec2::Y)
+    }
+
+    /// Splits the shape into a core point, segment or box and the radius
+    /// swept around it.
+    fn core(&self, pos: Vec2) -> (Core, f32) {
+        match *self {
+            Self::Circle { radius } => (Core::Point(pos), radius),
+            Self::Aabb { half_size } => (Core::Box(OrientedBox::new(pos, half_size, 0.)), 0.),
+            Self::Obb {
+                half_size,
+                rotation,
+            } => (Core::Box(OrientedBox::new(pos, half_size, rotation)), 0.),
+            Self::Capsule {
+                half_length,
+                radius,
+                rotation,
+            } => {
+                let axis = Vec2::from_angle(rotation).rotate(Vec2::Y) * half_length;
+                (Core::Segment(pos - axis, pos + axis), radius)
+            }
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug)]
+enum Core {
+    Point(Vec2),
+    Segment(Vec2, Vec2),
+    Box(OrientedBox),
+}
+
+#[derive(Clone, Copy, Debug)]
+struct OrientedBox {
+    center: Vec2,
+    half_size: Vec2,
+    rotation: Vec2,
+}
+
+impl OrientedBox {
+    fn new(center: Vec2, half_size: Vec2, angle: f32) -> Self {
+        Self {
+            center,
+            half_size,
+            rotation: Vec2::from_angle(angle),
+        }
+    }
+
+    fn local_point(&self, point: Vec2) -> Vec2 {
+        Vec2::new(self.rotation.x, -self.rotation.y).rotate(point - self.center)
+    }
+
+    fn axes(&self) -> [Vec2; 2] {
+        [self.rotation.rotate(Vec2::X), self.rotation.rotate(Vec2::Y)]
+    }
+
+    fn corners(&self) -> [Vec2; 4] {
+        let h = self.half_size;
+        [
+            Vec2::new(h.x, h.y),
+            Vec2::new(-h.x, h.y),
+            Vec2::new(-h.x, -h.y),
+            Vec2::new(h.x, -h.y),
+        ]
+        .map(|corner| self.center + self.rotation.rotate(corner))
+    }
+
+    fn distance_squared_to_point(&self, point: Vec2) -> f32 {
+        let local = self.local_point(point);
+        let clamped = local.clamp(-self.half_size, self.half_size);
+        local.distance_squared(clamped)
+    }
+
+    fn closest_point(&self, point: Vec2) -> Vec2 {
+        let local = self.local_point(point);
+        let clamped = local.clamp(-self.half_size, self.half_size);
+        self.center + self.rotation.rotate(clamped)
+    }
+
+    fn intersects_segment(&self, a: Vec2, b: Vec2) -> bool {
+        let local_a = self.local_point(a);
+        let local_b = self.local_point(b);
+        sweep_point_aabb(local_a, local_b - local_a, self.half_size).is_some()
+    }
+}
+
+fn distance_squared(a: &Core, b: &Core) -> f32 {
+    match (*a, *b) {
+        (Core::Point(p), Core::Point(q)) => p.distance_squared(q),
+        (Core::Point(p), Core::Segment(a, b)) | (Core::Segment(a, b), Core::Point(p)) => {
+            point_segment_distance_squared(p, a, b)
+        }
+        (Core::Point(p), Core::Box(obb)) | (Core::Box(obb), Core::Point(p)) => {
+            obb.distance_squared_to_point(p)
+        }
+        (Core::Segment(a, b), Core::Segment(c, d)) => {
+            if segments_intersect(a, b, c, d) {
+                return 0.;
+            }
+
+            [
+                point_segment_distance_squared(a, c, d),
+                point_segment_distance_squared(b, c, d),
+                point_segment_distance_squared(c, a, b),
+                point_segment_distance_squared(d, a, b),
+            ]
+            .into_iter()
+            .fold(f32::INFINITY, f32::min)
+        }
+        (Core::Segment(a, b), Core::Box(obb)) | (Core::Box(obb), Core::Segment(a, b)) => {
+            if obb.intersects_segment(a, b) {
+                return 0.;
+            }
+
+            let corners = obb.corners();
+
+            [
+                obb.distance_squared_to_point(a),
+                obb.distance_squared_to_point(b),
+            ]
+            .into_iter()
+            .chain(
+                corners
+                    .into_iter()
+                    .map(|corner| point_segment_distance_squared(corner, a, b)),
+            )
+            .fold(f32::INFINITY, f32::min)
+        }
+        (Core::Box(a), Core::Box(b)) => {
+            if boxes_overlap(&a, &b) {
+                0.
+            } else {
+                f32::INFINITY
+            }
+        }
+    }
+}
+
+fn point_segment_distance_squared(p: Vec2, a: Vec2, b: Vec2) -> f32 {
+    p.distance_squared(closest_point_on_segment(p, a, b))
+}
+
+fn closest_point_on_segment(p: Vec2, a: Vec2, b: Vec2) -> Vec2 {
+    let ab = b - a;
+    let len_sq = ab.length_squared();
+    if len_sq <= f32::EPSILON {
+        return a;
+    }
+
+    let t = ((p - a).dot(ab) / len_sq).clamp(0., 1.);
+    a + ab * t
+}
+
+fn segments_intersect(a: Vec2, b: Vec2, c: Vec2, d: Vec2) -> bool {
+    let ab = b - a;
+    let cd = d - c;
+
+    let denominator = ab.perp_dot(cd);
+    if denominator.abs() <= f32::EPSILON {
+        return false;
+    }
+
+    let t = (c - a).perp_dot(cd) / denominator;
+    let u = (c - a).perp_dot(ab) / denominator;
+
+    (0. ..=1.).contains(&t) && (0. ..=1.).contains(&u)
+}
+
+/// Separating axis test between two oriented boxes.
+fn boxes_overlap(a: &OrientedBox, b: &OrientedBox) -> bool {
+    let offset = b.center - a.center;
+
+    a.axes().into_iter().chain(b.axes()).all(|axis| {
+        let project = |obb: &OrientedBox| {
+            let [x, y] = obb.axes();
+            obb.half_size.x * x.dot(axis).abs() + obb.half_size.y * y.dot(axis).abs()
+        };
+
+        offset.dot(axis).abs() <= project(a) + project(b)
+    })
+}
+
+/// Time of impact in `[0, 1]` of a circle moving from `start` to `end` against a
+/// static oriented box, tested as a point against the box grown by `radius`.
+fn sweep_circle_obb(
+    start: Vec2,
+    end: Vec2,
+    radius: f32,
+    center: Vec2,
+    half_size: Vec2,
+    rotation: f32,
+) -> Option<f32> {
+    let obb = OrientedBox::new(center, half_size, rotation);
+    let local_start = obb.local_point(start);
+    let local_end = obb.local_point(end);
+    let delta = local_end - local_start;
+
+    let corners = [
+        Vec2::new(half_size.x, half_size.y),
+        Vec2::new(-half_size.x, half_size.y),
+        Vec2::new(half_size.x, -half_size.y),
+        Vec2::new(-half_size.x, -half_size.y),
+    ];
+
+    [
+        sweep_point_aabb(local_start, delta, half_size + Vec2::new(radius, 0.0)),
+        sweep_point_aabb(local_start, delta, half_size + Vec2::new(0.0, radius)),
+    ]
+    .into_iter()
+    .chain(
+        corners
+            .into_iter()
+            .map(|corner| sweep_point_circle(local_start, delta, corner, radius)),
+    )
+    .flatten()
+    .min_by(f32::total_cmp)
+}
+
+fn sweep_point_circle(start: Vec2, delta: Vec2, center: Vec2, radius: f32) -> Option<f32> {
+    let offset = start - center;
+    let c = offset.length_squared() - radius * radius;
+    if c <= 0.0 {
+        return Some(0.0);
+    }
+
+    let a = delta.length_squared();
+    let b = offset.dot(delta);
+    if a <= f32::EPSILON || b >= 0.0 {
+        return None;
+    }
+
+    let discriminant = b * b - a * c;
+    if discriminant < 0.0 {
+        return None;
+    }
+
+    let t = (-b - discriminant.sqrt()) / a;
+    (t <= 1.0).then_some(t)
+}
+
+fn sweep_point_aabb(start: Vec2, delta: Vec2, half_size: Vec2) -> Option<f32> {
+    let mut t_min = 0.0f32;
+    let mut t_max = 1.0f32;
+
+    for axis in 0..2 {
+        let (s, d, h) = (start[axis], delta[axis], half_size[axis]);
+
+        if d.abs() <= f32::EPSILON {
+            if s.abs() > h {
+                return None;
+            }
+            continue;
+        }
+
+        let t1 = (-h - s) / d;
+        let t2 = (h - s) / d;
+
+        t_min = t_min.max(t1.min(t2));
+        t_max = t_max.min(t1.max(t2));
+
+        if t_min > t_max {
+            return None;
+        }
+    }
+
+    Some(t_min)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::f32::consts::FRAC_PI_4;
+
+    #[test]
+    fn circle_circle() {
+        let a = Collider::circle(10.);
+        let b = Collider::circle(5.);
+
+        assert!(a.intersects(Vec2::ZERO, &b, Vec2::new(14., 0.)));
+        assert!(!a.intersects(Vec2::ZERO, &b, Vec2::new(16., 0.)));
+    }
+
+    #[test]
+    fn circle_aabb() {
+        let circle = Collider::circle(4.);
+        let aabb = Collider::aabb(Vec2::new(10., 5.));
+
+        assert!(circle.intersects(Vec2::new(13., 0.), &aabb, Vec2::ZERO));
+        assert!(!circle.intersects(Vec2::new(13., 8.), &aabb, Vec2::ZERO));
+        assert!(aabb.intersects(Vec2::ZERO, &circle, Vec2::new(0., 8.)));
+    }
+
+    #[test]
+    fn circle_obb() {
+        let circle = Collider::circle(1.);
+        let obb = Collider::obb(Vec2::new(10., 1.), FRAC_PI_4);
+
+        // along the rotated long axis
+        assert!(circle.intersects(Vec2::new(6., 6.), &obb, Vec2::ZERO));
+        // where the unrotated box would have been
+        assert!(!circle.intersects(Vec2::new(9., 0.), &obb, Vec2::ZERO));
+    }
+
+    #[test]
+    fn circle_capsule() {
+        let circle = Collider::circle(2.);
+        let capsule = Collider::capsule(10., 3., 0.);
+
+        assert!(circle.intersects(Vec2::new(4., 8.), &capsule, Vec2::ZERO));
+        assert!(circle.intersects(Vec2::new(0., 14.), &capsule, Vec2::ZERO));
+        assert!(!circle.intersects(Vec2::new(6., 0.), &capsule, Vec2::ZERO));
+    }
+
+    #[test]
+    fn obb_obb() {
+        let a = Collider::aabb(Vec2::splat(5.));
+        let b = Collider::obb(Vec2::splat(5.), FRAC_PI_4);
+
+        // the rotated corner reaches ~7.07 along x
+        assert!(a.intersects(Vec2::ZERO, &b, Vec2::new(11., 0.)));
+        assert!(!a.intersects(Vec2::ZERO, &b, Vec2::new(13., 0.)));
+        // but not along the diagonal
+        assert!(!a.intersects(Vec2::ZERO, &b, Vec2::new(11., 11.)));
+    }
+
+    #[test]
+    fn obb_capsule() {
+        let obb = Collider::obb(Vec2::new(10., 1.), 0.);
+        let capsule = Collider::capsule(5., 1., 0.);
+
+        // crossing the box through its middle
+        assert!(obb.intersects(Vec2::ZERO, &capsule, Vec2::ZERO));
+        assert!(obb.intersects(Vec2::ZERO, &capsule, Vec2::new(0., 7.)));
+        assert!(!obb.intersects(Vec2::ZERO, &capsule, Vec2::new(0., 8.)));
+    }
+
+    #[test]
+    fn capsule_capsule() {
+        let vertical = Collider::capsule(10., 1., 0.);
+        let horizontal = Collider::capsule(10., 1., std::f32::consts::FRAC_PI_2);
+
+        assert!(vertical.intersects(Vec2::ZERO, &horizontal, Vec2::new(5., 5.)));
+        assert!(vertical.intersects(Vec2::ZERO, &vertical, Vec2::new(1.5, 0.)));
+        assert!(!vertical.intersects(Vec2::ZERO, &vertical, Vec2::new(3., 0.)));
+        assert!(!vertical.intersects(Vec2::ZERO, &horizontal, Vec2::new(0., 13.)));
+    }
+
+    #[test]
+    fn sweep_circle_hits_before_tunneling() {
+        let target = Collider::circle(16.);
+        let t = target.sweep_circle(Vec2::ZERO, Vec2::new(-100., 0.), Vec2::new(100., 0.), 16.);
+
+        assert!(t.is_some_and(|t| (t - 0.34).abs() < 1e-4));
+        assert!(target
+            .sweep_circle(Vec2::ZERO, Vec2::new(-100., 40.), Vec2::new(100., 40.), 16.)
+            .is_none());
+    }
+
+    #[test]
+    fn sweep_circle_obb_and_capsule() {
+        let obb = Collider::obb(Vec2::new(8., 4.), 0.);
+        let t = obb.sweep_circle(Vec2::ZERO, Vec2::new(0., 20.), Vec2::new(0., -20.), 2.);
+        assert!(t.is_some_and(|t| (t - 0.35).abs() < 1e-4));
+
+        let capsule = Collider::capsule(10., 2., 0.);
+        let t = capsule.sweep_circle(Vec2::ZERO, Vec2::new(0., 30.), Vec2::new(0., -30.), 2.);
+        assert!(t.is_some_and(|t| (t - 0.2667).abs() < 1e-3));
+    }
+
+    #[test]
+    fn normals_point_away_from_the_surface() {
+        let aabb = Collider::aabb(Vec2::new(50., 10.));
+        let normal = aabb.normal_at(Vec2::ZERO, Vec2::new(45., 20.));
+        assert!(normal.abs_diff_eq(Vec2::Y, 1e-5));
+
+        let capsule = Collider::capsule(10., 1., 0.);
+        let normal = capsule.normal_at(Vec2::ZERO, Vec2::new(-5., 3.));
+        assert!(normal.abs_diff_eq(Vec2::NEG_X, 1e-5));
+    }
+
+    #[test]
+    fn sweep_circle_starting_inside() {
+        let target = Collider::aabb(Vec2::splat(10.));
+        let t = target.sweep_circle(Vec2::ZERO, Vec2::ZERO, Vec2::new(50., 0.), 1.);
+
+        assert_eq!(t, Some(0.));
+    }
+}
@@ -1,21 +1,29 @@
+use std::{any::type_name, fmt, io, marker::PhantomData, path::Path, str::FromStr};
+
+use bevy::asset::{io::Reader, AssetLoader, LoadContext};
 use bevy::prelude::*;
-use bevy::reflect::TypePath;
-use serde::Deserialize;
+use bevy::reflect::{PartialReflect, Struct};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
 
-#[derive(Asset, TypePath, Debug, Deserialize, Clone)]
+#[derive(Asset, Reflect, Debug, Serialize, Deserialize, Clone, PartialEq)]
 #[serde(default)]
 pub struct GameConfig {
+    pub fixed_tick_rate: f64,
+
     pub gravity: f32,
     pub charging_gravity_multiplier: f32,
     pub movement_smoothing: f32,
     pub air_friction: f32,
     pub ground_friction: f32,
+    pub max_speed: f32,
     pub floor_y: f32,
     pub x_limit: f32,
     pub ceiling_y: f32,
     pub spring_force: f32,
     pub max_pull: f32,
 
+    pub player_health: u8,
+    pub player_hit_immunity_duration: f32,
     pub player_x_acceleration: f32,
     pub player_y_acceleration: f32,
     pub player_max_x_speed: f32,
@@ -25,6 +33,29 @@ pub struct GameConfig {
     pub player_dash_duration: f32,
     pub player_dash_immunity_duration: f32,
     pub player_dash_speed: f32,
+    pub dash_input_buffer: f32,
+    pub dash_chain_grace: f32,
+
+    pub hit_impulse: f32,
+    pub hit_restitution: f32,
+    pub hit_stun_duration: f32,
+    pub weak_spot_restitution: f32,
+    pub dash_knockback_impulse: f32,
+    pub nuke_knockback_impulse: f32,
+
+    pub enemy_min_speed: f32,
+    pub enemy_max_speed: f32,
+    pub enemy_respawn_threshold: u32,
+    pub enemy_respawn_min_delay: f32,
+    pub enemy_respawn_max_delay: f32,
+
+    pub turret_turn_speed: f32,
+    pub turret_max_aim_angle: f32,
+    pub turret_reload_duration: f32,
+    pub turret_burst_count: u32,
+    pub turret_burst_interval: f32,
+    pub turret_projectile_speed: f32,
+    pub turret_projectile_lifetime: f32,
 
     pub cam_min_y: f32,
     pub cam_max_y: f32,
@@ -36,16 +67,20 @@ pub struct GameConfig {
 impl Default for GameConfig {
     fn default() -> Self {
         Self {
+            fixed_tick_rate: 64.0,
             gravity: -42.0,
             charging_gravity_multiplier: 0.2,
             movement_smoothing: 8.0,
             air_friction: 2.0,
             ground_friction: 6.0,
+            max_speed: 4000.0,
             floor_y: -160.0,
             x_limit: 1000.0,
             ceiling_y: 300.0,
             spring_force: 6.0,
             max_pull: -280.0,
+            player_health: 3,
+            player_hit_immunity_duration: 1.0,
             player_x_acceleration: 3200.0,
             player_y_acceleration: 2200.0,
             player_max_x_speed: 240.0,
@@ -55,6 +90,26 @@ impl Default for GameConfig {
             player_dash_duration: 0.12,
             player_dash_immunity_duration: 1.0,
             player_dash_speed: 3000.0,
+            dash_input_buffer: 0.2,
+            dash_chain_grace: 0.25,
+            hit_impulse: 120.0,
+            hit_restitution: 0.8,
+            hit_stun_duration: 0.2,
+            weak_spot_restitution: 0.6,
+            dash_knockback_impulse: 400.0,
+            nuke_knockback_impulse: 600.0,
+            enemy_min_speed: 80.0,
+            enemy_max_speed: 100.0,
+            enemy_respawn_threshold: 3,
+            enemy_respawn_min_delay: 2.0,
+            enemy_respawn_max_delay: 4.0,
+            turret_turn_speed: 1.5,
+            turret_max_aim_angle: 1.2,
+            turret_reload_duration: 2.5,
+            turret_burst_count: 3,
+            turret_burst_interval: 0.15,
+            turret_projectile_speed: 260.0,
+            turret_projectile_lifetime: 3.0,
             cam_min_y: -30.0,
             cam_max_y: 60.0,
             cam_min_x: -900.0,
@@ -64,7 +119,536 @@ impl Default for GameConfig {
     }
 }
 
+impl Validate for GameConfig {
+    fn validate(&self) -> Vec<String> {
+        let mut problems = Vec::new();
+
+        for (name, value) in [
+            ("fixed_tick_rate", self.fixed_tick_rate),
+            (
+                "player_charging_power_duration",
+                self.player_charging_power_duration.into(),
+            ),
+            ("player_dash_duration", self.player_dash_duration.into()),
+            (
+                "player_dash_immunity_duration",
+                self.player_dash_immunity_duration.into(),
+            ),
+            (
+                "player_hit_immunity_duration",
+                self.player_hit_immunity_duration.into(),
+            ),
+            ("hit_stun_duration", self.hit_stun_duration.into()),
+            (
+                "enemy_respawn_min_delay",
+                self.enemy_respawn_min_delay.into(),
+            ),
+            ("turret_reload_duration", self.turret_reload_duration.into()),
+            ("turret_burst_interval", self.turret_burst_interval.into()),
+            (
+                "turret_projectile_lifetime",
+                self.turret_projectile_lifetime.into(),
+            ),
+        ] {
+            if value <= 0. {
+                problems.push(format!("{name} must be greater than 0, got {value}"));
+            }
+        }
+
+        // zero turns these off
+        for (name, value) in [
+            ("dash_input_buffer", self.dash_input_buffer),
+            ("dash_chain_grace", self.dash_chain_grace),
+        ] {
+            if value < 0. {
+                problems.push(format!("{name} must not be negative, got {value}"));
+            }
+        }
+
+        for (name, value) in [
+            ("turret_max_aim_angle", self.turret_max_aim_angle),
+            ("turret_turn_speed", self.turret_turn_speed),
+        ] {
+            if value < 0. {
+                problems.push(format!("{name} must not be negative, got {value}"));
+            }
+        }
+
+        if self.turret_burst_count == 0 {
+            problems.push("turret_burst_count must be at least 1".to_string());
+        }
+
+        if self.floor_y >= self.ceiling_y {
+            problems.push(format!(
+                "floor_y ({}) must be below ceiling_y ({})",
+                self.floor_y, self.ceiling_y
+            ));
+        }
+
+        if self.player_health == 0 {
+            problems.push("player_health must be at least 1".to_string());
+        }
+
+        for (min, min_value, max, max_value) in [
+            (
+                "enemy_min_speed",
+                self.enemy_min_speed,
+                "enemy_max_speed",
+                self.enemy_max_speed,
+            ),
+            (
+                "enemy_respawn_min_delay",
+                self.enemy_respawn_min_delay,
+                "enemy_respawn_max_delay",
+                self.enemy_respawn_max_delay,
+            ),
+            ("cam_min_x", self.cam_min_x, "cam_max_x", self.cam_max_x),
+            ("cam_min_y", self.cam_min_y, "cam_max_y", self.cam_max_y),
+        ] {
+            if min_value > max_value {
+                problems.push(format!(
+                    "{min} ({min_value}) must not be above {max} ({max_value})"
+                ));
+            }
+        }
+
+        problems
+    }
+}
+
 #[derive(Resource, Debug)]
 pub struct Config {
+    /// As loaded from the config file.
+    pub base: GameConfig,
+    /// `base` with the selected difficulty applied. Gameplay reads this one.
     pub game: GameConfig,
 }
+
+impl Config {
+    pub fn new(base: GameConfig) -> Self {
+        Self {
+            game: base.clone(),
+            base,
+        }
+    }
+}
+
+impl ConfigResource for Config {
+    type Asset = GameConfig;
+
+    fn asset_mut(&mut self) -> &mut GameConfig {
+        &mut self.base
+    }
+}
+
+/// A resource holding a copy of a config asset, kept in sync with the file
+/// by [`ConfigReloadPlugin`].
+pub trait ConfigResource: Resource {
+    type Asset: Asset + Struct + Clone;
+
+    fn asset_mut(&mut self) -> &mut Self::Asset;
+}
+
+/// Handle of the asset a [`ConfigResource`] was loaded from. Holding it keeps
+/// the asset alive so file changes are picked up.
+#[derive(Resource)]
+pub struct ConfigHandle<A: Asset>(pub Handle<A>);
+
+/// Copies changes of a config asset into its resource while the game runs.
+pub struct ConfigReloadPlugin<R>(PhantomData<R>);
+
+impl<R> Default for ConfigReloadPlugin<R> {
+    fn default() -> Self {
+        Self(PhantomData)
+    }
+}
+
+impl<R: ConfigResource> Plugin for ConfigReloadPlugin<R> {
+    fn build(&self, app: &mut App) {
+        app.add_systems(PreUpdate, reload_config_system::<R>);
+    }
+}
+
+fn reload_config_system<R: ConfigResource>(
+    mut events: EventReader<AssetEvent<R::Asset>>,
+    handle: Option<Res<ConfigHandle<R::Asset>>>,
+    assets: Res<Assets<R::Asset>>,
+    resource: Option<ResMut<R>>,
+) {
+    let (Some(handle), Some(mut resource)) = (handle, resource) else {
+        events.clear();
+        return;
+    };
+
+    // the first load can also land after the resource was inserted
+    let modified = events.read().any(|event| {
+        event.is_modified(handle.0.id()) || event.is_loaded_with_dependencies(handle.0.id())
+    });
+
+    let Some(asset) = assets.get(handle.0.id()).filter(|_| modified) else {
+        return;
+    };
+
+    let changed = changed_fields(resource.asset_mut(), asset);
+    if changed.is_empty() {
+        return;
+    }
+
+    log::info!(
+        "Reloaded {}: {}",
+        R::Asset::short_type_path(),
+        changed.join(", ")
+    );
+
+    *resource.asset_mut() = asset.clone();
+}
+
+/// A `key=value` override of a [`GameConfig`] field, checked against the
+/// field's type when parsed.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ConfigOverride {
+    pub key: String,
+    pub value: String,
+}
+
+impl FromStr for ConfigOverride {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, String> {
+        let (key, value) = s
+            .split_once('=')
+            .ok_or_else(|| format!("expected KEY=VALUE, got `{s}`"))?;
+
+        let config_override = Self {
+            key: key.trim().to_string(),
+            value: value.trim().to_string(),
+        };
+        config_override.apply(&mut GameConfig::default())?;
+
+        Ok(config_override)
+    }
+}
+
+impl ConfigOverride {
+    pub fn apply(&self, cfg: &mut GameConfig) -> Result<(), String> {
+        set_field(cfg, &self.key, &self.value)
+    }
+}
+
+/// Sets field `key` of `target` from text, parsed as the field's type.
+pub fn set_field(target: &mut dyn Struct, key: &str, value: &str) -> Result<(), String> {
+    let field = target
+        .field_mut(key)
+        .ok_or_else(|| format!("unknown config key `{key}`"))?;
+
+    parse_into::<f32>(field, value)
+        .or_else(|| parse_into::<f64>(field, value))
+        .or_else(|| parse_into::<u32>(field, value))
+        .or_else(|| parse_into::<u8>(field, value))
+        .unwrap_or_else(|| Err("has an unsupported type".to_string()))
+        .map_err(|err| format!("`{key}` {err}"))
+}
+
+fn parse_into<T: FromStr + PartialReflect>(
+    field: &mut dyn PartialReflect,
+    value: &str,
+) -> Option<Result<(), String>> {
+    let field = field.try_downcast_mut::<T>()?;
+
+    Some(match value.parse() {
+        Ok(value) => {
+            *field = value;
+            Ok(())
+        }
+        Err(_) => Err(format!("expects {}, got `{value}`", type_name::<T>())),
+    })
+}
+
+/// `name: old -> new` for every field that differs between the two values.
+pub fn changed_fields(old: &dyn Struct, new: &dyn Struct) -> Vec<String> {
+    (0..old.field_len())
+        .filter_map(|i| {
+            let name = old.name_at(i)?;
+            let old = old.field_at(i)?;
+            let new = new.field(name)?;
+
+            (old.reflect_partial_eq(new) != Some(true))
+                .then(|| format!("{name}: {old:?} -> {new:?}"))
+        })
+        .collect()
+}
+
+/// Range checks run whenever a config asset is loaded.
+pub trait Validate {
+    /// One readable line per offending field; empty when the value is usable.
+    fn validate(&self) -> Vec<String>;
+}
+
+/// Text formats data assets can be written in, picked by file extension.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Format {
+    Json,
+    /// Allows comments, which JSON doesn't.
+    Ron,
+}
+
+impl Format {
+    pub fn from_path(path: &Path) -> Option<Self> {
+        match path.extension()?.to_str()? {
+            "json" => Some(Format::Json),
+            "ron" => Some(Format::Ron),
+            _ => None,
+        }
+    }
+
+    /// Pretty-printed and tab-indented like the files under `assets/`.
+    pub fn serialize<A: Serialize>(self, value: &A) -> io::Result<String> {
+        match self {
+            Format::Json => {
+                let mut bytes = Vec::new();
+                let formatter = serde_json::ser::PrettyFormatter::with_indent(b"\t");
+                value.serialize(&mut serde_json::Serializer::with_formatter(
+                    &mut bytes, formatter,
+                ))?;
+                String::from_utf8(bytes).map_err(io::Error::other)
+            }
+            Format::Ron => {
+                let pretty = ron::ser::PrettyConfig::new()
+                    .indentor("\t")
+                    .extensions(ron::extensions::Extensions::IMPLICIT_SOME);
+                ron::ser::to_string_pretty(value, pretty).map_err(io::Error::other)
+            }
+        }
+    }
+}
+
+/// Registers data asset `A` with a loader that reads JSON or RON depending on
+/// the file extension, warns about unknown keys and rejects values that fail
+/// [`Validate`], naming the offending fields.
+pub struct ConfigAssetPlugin<A> {
+    extensions: &'static [&'static str],
+    _marker: PhantomData<A>,
+}
+
+impl<A> ConfigAssetPlugin<A> {
+    pub fn new(extensions: &'static [&'static str]) -> Self {
+        Self {
+            extensions,
+            _marker: PhantomData,
+        }
+    }
+}
+
+impl<A: Asset + DeserializeOwned + Validate> Plugin for ConfigAssetPlugin<A> {
+    fn build(&self, app: &mut App) {
+        app.init_asset::<A>()
+            .register_asset_loader(ConfigLoader::<A> {
+                extensions: self.extensions,
+                _marker: PhantomData,
+            });
+    }
+}
+
+struct ConfigLoader<A> {
+    extensions: &'static [&'static str],
+    _marker: PhantomData<A>,
+}
+
+#[derive(Debug)]
+pub enum ConfigError {
+    Io(io::Error),
+    UnknownFormat,
+    Json(serde_json::Error),
+    Ron(ron::error::SpannedError),
+    Invalid(Vec<String>),
+}
+
+impl fmt::Display for ConfigError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ConfigError::Io(err) => write!(f, "could not read the file: {err}"),
+            ConfigError::UnknownFormat => write!(f, "expected a .json or .ron file"),
+            ConfigError::Json(err) => write!(f, "could not parse the JSON: {err}"),
+            ConfigError::Ron(err) => write!(f, "could not parse the RON: {err}"),
+            ConfigError::Invalid(problems) => {
+                write!(f, "invalid values:")?;
+                for problem in problems {
+                    write!(f, "\n  - {problem}")?;
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+impl std::error::Error for ConfigError {}
+
+/// A parsed config along with the keys that didn't match any of its fields.
+pub struct Parsed<A> {
+    pub config: A,
+    pub unknown_keys: Vec<String>,
+}
+
+pub fn parse_config<A: DeserializeOwned + Validate>(
+    bytes: &[u8],
+    format: Format,
+) -> Result<Parsed<A>, ConfigError> {
+    let mut unknown_keys = Vec::new();
+    let mut unknown = |path: serde_ignored::Path| unknown_keys.push(path.to_string());
+
+    let config: A = match format {
+        Format::Json => {
+            let mut deserializer = serde_json::Deserializer::from_slice(bytes);
+            serde_ignored::deserialize(&mut deserializer, &mut unknown)
+                .and_then(|config| deserializer.end().map(|()| config))
+                .map_err(ConfigError::Json)?
+        }
+        Format::Ron => {
+            let mut deserializer =
+                ron::Deserializer::from_bytes(bytes).map_err(ConfigError::Ron)?;
+            serde_ignored::deserialize(&mut deserializer, &mut unknown)
+                .and_then(|config| deserializer.end().map(|()| config))
+                .map_err(|err| ConfigError::Ron(deserializer.span_error(err)))?
+        }
+    };
+
+    let problems = config.validate();
+    if !problems.is_empty() {
+        return Err(ConfigError::Invalid(problems));
+    }
+
+    Ok(Parsed {
+        config,
+        unknown_keys,
+    })
+}
+
+impl<A: Asset + DeserializeOwned + Validate> AssetLoader for ConfigLoader<A> {
+    type Asset = A;
+    type Settings = ();
+    type Error = ConfigError;
+
+    async fn load(
+        &self,
+        reader: &mut dyn Reader,
+        _settings: &(),
+        load_context: &mut LoadContext<'_>,
+    ) -> Result<A, ConfigError> {
+        let mut bytes = Vec::new();
+        reader
+            .read_to_end(&mut bytes)
+            .await
+            .map_err(ConfigError::Io)?;
+
+        let format = Format::from_path(load_context.path()).ok_or(ConfigError::UnknownFormat)?;
+        let parsed = parse_config::<A>(&bytes, format)?;
+
+        // with #[serde(default)] a typo would otherwise silently keep the default
+        for key in parsed.unknown_keys {
+            log::warn!(
+                "Unknown key `{key}` in {}, ignoring it",
+                load_context.path().display()
+            );
+        }
+
+        Ok(parsed.config)
+    }
+
+    fn extensions(&self) -> &[&str] {
+        self.extensions
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn lists_only_changed_fields() {
+        let old = GameConfig::default();
+        let new = GameConfig {
+            gravity: -30.,
+            turret_burst_count: 5,
+            ..old.clone()
+        };
+
+        assert_eq!(
+            changed_fields(&old, &new),
+            ["gravity: -42.0 -> -30.0", "turret_burst_count: 3 -> 5"]
+        );
+        assert!(changed_fields(&old, &old.clone()).is_empty());
+    }
+
+    #[test]
+    fn overrides_are_type_checked() {
+        let mut cfg = GameConfig::default();
+
+        "gravity=-12.5"
+            .parse::<ConfigOverride>()
+            .unwrap()
+            .apply(&mut cfg)
+            .unwrap();
+        assert_eq!(cfg.gravity, -12.5);
+
+        assert_eq!(
+            "turret_burst_count=1.5".parse::<ConfigOverride>(),
+            Err("`turret_burst_count` expects u32, got `1.5`".to_string())
+        );
+        assert_eq!(
+            "gravty=1".parse::<ConfigOverride>(),
+            Err("unknown config key `gravty`".to_string())
+        );
+        assert!("gravity".parse::<ConfigOverride>().is_err());
+    }
+
+    #[test]
+    fn shipped_config_is_valid() {
+        let bytes = std::fs::read("assets/config/game.config.json").unwrap();
+
+        match parse_config::<GameConfig>(&bytes, Format::Json) {
+            Ok(parsed) => assert_eq!(parsed.unknown_keys, Vec::<String>::new()),
+            Err(err) => panic!("{err}"),
+        }
+    }
+
+    #[test]
+    fn reports_typos_and_bad_ranges() {
+        let parsed =
+            parse_config::<GameConfig>(br#"{ "gravty": -10.0, "gravity": -10.0 }"#, Format::Json)
+                .unwrap();
+        assert_eq!(parsed.unknown_keys, ["gravty"]);
+        assert_eq!(parsed.config.gravity, -10.);
+
+        let err = parse_config::<GameConfig>(
+            br#"{
+                "player_dash_duration": 0.0,
+                "turret_burst_count": 0,
+                "floor_y": 400.0,
+                "ceiling_y": 300.0
+            }"#,
+            Format::Json,
+        )
+        .err()
+        .unwrap();
+
+        assert_eq!(
+            err.to_string(),
+            "invalid values:\n  \
+             - player_dash_duration must be greater than 0, got 0\n  \
+             - turret_burst_count must be at least 1\n  \
+             - floor_y (400) must be below ceiling_y (300)"
+        );
+    }
+
+    #[test]
+    fn reads_ron_with_comments() {
+        let parsed = parse_config::<GameConfig>(
+            b"// designers' notes\n(gravity: -10.0, gravty: 1.0)",
+            Format::Ron,
+        )
+        .unwrap();
+
+        assert_eq!(parsed.config.gravity, -10.);
+        assert_eq!(parsed.unknown_keys, ["gravty"]);
+    }
+}
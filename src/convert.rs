@@ -0,0 +1,98 @@
+use std::path::Path;
+
+use serde::{de::DeserializeOwned, Serialize};
+
+use crate::{
+    config::{parse_config, Format, GameConfig, Validate},
+    difficulty::DifficultyPresets,
+    level::LevelData,
+};
+
+/// Converts a data asset between JSON and RON, both ways. The asset type comes
+/// from the file name (`*.config.*`, `*.presets.*`, `*.level.*`). Comments are
+/// not carried over.
+pub fn convert(input: &Path, output: &Path) -> Result<(), String> {
+    let kind = asset_kind(input)?;
+    if asset_kind(output)? != kind {
+        return Err(format!(
+            "{} and {} hold different kinds of assets",
+            input.display(),
+            output.display()
+        ));
+    }
+
+    let contents = match kind {
+        "config" => reformat::<GameConfig>(input, output)?,
+        "presets" => reformat::<DifficultyPresets>(input, output)?,
+        "level" => reformat::<LevelData>(input, output)?,
+        _ => return Err(format!("don't know how to convert {kind} files")),
+    };
+
+    std::fs::write(output, contents)
+        .map_err(|err| format!("could not write {}: {err}", output.display()))
+}
+
+/// `config` for `game.config.json`.
+fn asset_kind(path: &Path) -> Result<&str, String> {
+    path.file_stem()
+        .map(Path::new)
+        .and_then(Path::extension)
+        .and_then(|kind| kind.to_str())
+        .ok_or_else(|| format!("can't tell the asset type of {}", path.display()))
+}
+
+fn format(path: &Path) -> Result<Format, String> {
+    Format::from_path(path).ok_or_else(|| format!("{} is not a .json or .ron file", path.display()))
+}
+
+fn reformat<A: Serialize + DeserializeOwned + Validate>(
+    input: &Path,
+    output: &Path,
+) -> Result<String, String> {
+    let bytes =
+        std::fs::read(input).map_err(|err| format!("could not read {}: {err}", input.display()))?;
+
+    let parsed = parse_config::<A>(&bytes, format(input)?)
+        .map_err(|err| format!("{}: {err}", input.display()))?;
+
+    for key in parsed.unknown_keys {
+        eprintln!("Dropping unknown key `{key}` from {}", input.display());
+    }
+
+    format(output)?
+        .serialize(&parsed.config)
+        .map_err(|err| err.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn round_trip<A: Serialize + DeserializeOwned + Validate>(path: &str) {
+        let json = std::fs::read(path).unwrap();
+        let config = parse_config::<A>(&json, Format::Json).unwrap().config;
+
+        let ron = Format::Ron.serialize(&config).unwrap();
+        let parsed = parse_config::<A>(ron.as_bytes(), Format::Ron).unwrap_or_else(|err| {
+            panic!("{path} doesn't survive RON: {err}\n{ron}");
+        });
+        assert!(parsed.unknown_keys.is_empty());
+
+        let json = Format::Json.serialize(&parsed.config).unwrap();
+        assert_eq!(json, Format::Json.serialize(&config).unwrap());
+    }
+
+    #[test]
+    fn shipped_assets_round_trip_through_ron() {
+        round_trip::<GameConfig>("assets/config/game.config.json");
+        round_trip::<DifficultyPresets>("assets/config/difficulty.presets.json");
+        round_trip::<LevelData>("assets/levels/city.level.json");
+    }
+
+    #[test]
+    fn asset_kind_comes_from_the_file_name() {
+        assert_eq!(asset_kind(Path::new("a/game.config.json")), Ok("config"));
+        assert_eq!(asset_kind(Path::new("city.level.ron")), Ok("level"));
+        assert!(asset_kind(Path::new("game.json")).is_err());
+    }
+}
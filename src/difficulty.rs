@@ -0,0 +1,432 @@
+use bevy::{prelude::*, reflect::Struct};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    args::Args,
+    config::{Config, ConfigOverride, ConfigResource, GameConfig, Validate},
+    game_state::GameState,
+    replay::is_playing_back,
+    storage,
+};
+
+const DIFFICULTY_KEY: &str = "difficulty";
+
+/// Selected in the main menu and applied on top of the base config.
+#[derive(Resource, Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Difficulty {
+    Easy,
+    #[default]
+    Normal,
+    Hard,
+    /// The base config as it is, without any preset.
+    Custom,
+}
+
+impl Difficulty {
+    pub const ALL: [Difficulty; 4] = [
+        Difficulty::Easy,
+        Difficulty::Normal,
+        Difficulty::Hard,
+        Difficulty::Custom,
+    ];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Difficulty::Easy => "Easy",
+            Difficulty::Normal => "Normal",
+            Difficulty::Hard => "Hard",
+            Difficulty::Custom => "Custom",
+        }
+    }
+
+    pub fn next(self) -> Self {
+        Self::ALL[(self as usize + 1) % Self::ALL.len()]
+    }
+
+    pub fn from_index(index: u8) -> Option<Self> {
+        Self::ALL.get(index as usize).copied()
+    }
+}
+
+/// A partial [`GameConfig`]; unset fields keep the base config's value.
+#[derive(Reflect, Debug, Serialize, Deserialize, Clone, Default, PartialEq)]
+#[serde(default)]
+pub struct Preset {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub player_health: Option<u8>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub player_hit_immunity_duration: Option<f32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub player_charging_power_duration: Option<f32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub enemy_min_speed: Option<f32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub enemy_max_speed: Option<f32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub enemy_respawn_threshold: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub enemy_respawn_min_delay: Option<f32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub enemy_respawn_max_delay: Option<f32>,
+}
+
+impl Preset {
+    /// Whether this preset overrides the config field named `field`.
+    pub fn sets(&self, field: &str) -> bool {
+        self.field(field)
+            .and_then(|value| value.reflect_ref().as_enum().ok())
+            .is_some_and(|value| value.variant_name() == "Some")
+    }
+
+    pub fn apply(&self, cfg: &mut GameConfig) {
+        let Preset {
+            player_health,
+            player_hit_immunity_duration,
+            player_charging_power_duration,
+            enemy_min_speed,
+            enemy_max_speed,
+            enemy_respawn_threshold,
+            enemy_respawn_min_delay,
+            enemy_respawn_max_delay,
+        } = self.clone();
+
+        if let Some(value) = player_health {
+            cfg.player_health = value;
+        }
+        if let Some(value) = player_hit_immunity_duration {
+            cfg.player_hit_immunity_duration = value;
+        }
+        if let Some(value) = player_charging_power_duration {
+            cfg.player_charging_power_duration = value;
+        }
+        if let Some(value) = enemy_min_speed {
+            cfg.enemy_min_speed = value;
+        }
+        if let Some(value) = enemy_max_speed {
+            cfg.enemy_max_speed = value;
+        }
+        if let Some(value) = enemy_respawn_threshold {
+            cfg.enemy_respawn_threshold = value;
+        }
+        if let Some(value) = enemy_respawn_min_delay {
+            cfg.enemy_respawn_min_delay = value;
+        }
+        if let Some(value) = enemy_respawn_max_delay {
+            cfg.enemy_respawn_max_delay = value;
+        }
+    }
+}
+
+#[derive(Asset, Reflect, Debug, Serialize, Deserialize, Clone, Default)]
+#[serde(default)]
+pub struct DifficultyPresets {
+    pub easy: Preset,
+    pub normal: Preset,
+    pub hard: Preset,
+}
+
+impl DifficultyPresets {
+    pub fn get(&self, difficulty: Difficulty) -> Option<&Preset> {
+        match difficulty {
+            Difficulty::Easy => Some(&self.easy),
+            Difficulty::Normal => Some(&self.normal),
+            Difficulty::Hard => Some(&self.hard),
+            Difficulty::Custom => None,
+        }
+    }
+}
+
+/// Only what a preset gets wrong on its own. How it combines with the base
+/// config is checked when it is applied, in [`build_game_config_system`].
+impl Validate for Preset {
+    fn validate(&self) -> Vec<String> {
+        let mut problems = Vec::new();
+
+        for (name, value) in [
+            (
+                "player_hit_immunity_duration",
+                self.player_hit_immunity_duration,
+            ),
+            (
+                "player_charging_power_duration",
+                self.player_charging_power_duration,
+            ),
+            ("enemy_respawn_min_delay", self.enemy_respawn_min_delay),
+        ] {
+            if let Some(value) = value.filter(|value| *value <= 0.) {
+                problems.push(format!("{name} must be greater than 0, got {value}"));
+            }
+        }
+
+        if self.player_health == Some(0) {
+            problems.push("player_health must be at least 1".to_string());
+        }
+
+        for (min, min_value, max, max_value) in [
+            (
+                "enemy_min_speed",
+                self.enemy_min_speed,
+                "enemy_max_speed",
+                self.enemy_max_speed,
+            ),
+            (
+                "enemy_respawn_min_delay",
+                self.enemy_respawn_min_delay,
+                "enemy_respawn_max_delay",
+                self.enemy_respawn_max_delay,
+            ),
+        ] {
+            if let (Some(min_value), Some(max_value)) = (min_value, max_value) {
+                if min_value > max_value {
+                    problems.push(format!(
+                        "{min} ({min_value}) must not be above {max} ({max_value})"
+                    ));
+                }
+            }
+        }
+
+        problems
+    }
+}
+
+impl Validate for DifficultyPresets {
+    fn validate(&self) -> Vec<String> {
+        Difficulty::ALL
+            .into_iter()
+            .filter_map(|difficulty| Some((difficulty, self.get(difficulty)?)))
+            .flat_map(|(difficulty, preset)| {
+                preset
+                    .validate()
+                    .into_iter()
+                    .map(move |problem| format!("{}: {problem}", difficulty.label()))
+            })
+            .collect()
+    }
+}
+
+/// Presets and overrides left out of the effective config because they made
+/// it invalid, one line each, shown in the main menu.
+#[derive(Resource, Debug, Default, PartialEq)]
+pub struct RejectedConfig(pub Vec<String>);
+
+#[derive(Resource, Debug, Default)]
+pub struct Presets {
+    pub presets: DifficultyPresets,
+}
+
+impl ConfigResource for Presets {
+    type Asset = DifficultyPresets;
+
+    fn asset_mut(&mut self) -> &mut DifficultyPresets {
+        &mut self.presets
+    }
+}
+
+pub struct DifficultyPlugin;
+
+impl Plugin for DifficultyPlugin {
+    fn build(&self, app: &mut App) {
+        let difficulty = storage::load::<Difficulty>(DIFFICULTY_KEY).unwrap_or_default();
+
+        app.insert_resource(difficulty)
+            .init_resource::<RejectedConfig>()
+            .add_systems(
+                PreUpdate,
+                // replays bring their own config
+                build_game_config_system.run_if(
+                    resource_exists::<Config>
+                        .and(resource_exists::<Presets>)
+                        .and(not(is_playing_back)),
+                ),
+            )
+            .add_systems(
+                Update,
+                save_difficulty_system.run_if(
+                    resource_changed::<Difficulty>
+                        .and(in_state(GameState::MainMenu))
+                        .and(not(is_playing_back)),
+                ),
+            );
+    }
+}
+
+/// Rebuilds the effective config whenever the config file, the presets or
+/// the difficulty change.
+fn build_game_config_system(
+    mut config: ResMut<Config>,
+    mut rejected: ResMut<RejectedConfig>,
+    presets: Res<Presets>,
+    difficulty: Res<Difficulty>,
+    args: Res<Args>,
+) {
+    if !(config.is_changed() || presets.is_changed() || difficulty.is_changed()) {
+        return;
+    }
+
+    let preset = presets
+        .presets
+        .get(*difficulty)
+        .map(|preset| (difficulty.label(), preset));
+    let (game, problems) = effective_config(&config.base, preset, &args.overrides);
+
+    for problem in &problems {
+        log::warn!("Not applied: {problem}");
+    }
+
+    if config.game != game {
+        config.game = game;
+    }
+    rejected.set_if_neq(RejectedConfig(problems));
+}
+
+/// The config file, then the difficulty preset, then command-line overrides,
+/// so an override always wins. A preset or override that would make the
+/// config invalid, e.g. a min above the base's max, is left out and
+/// described in the returned problems instead.
+fn effective_config(
+    base: &GameConfig,
+    preset: Option<(&str, &Preset)>,
+    overrides: &[ConfigOverride],
+) -> (GameConfig, Vec<String>) {
+    let mut game = base.clone();
+    let mut problems = Vec::new();
+
+    let mut apply = |layer: String, edit: &dyn Fn(&mut GameConfig) -> Result<(), String>| {
+        let mut candidate = game.clone();
+        let result = edit(&mut candidate).and_then(|()| match candidate.validate() {
+            invalid if invalid.is_empty() => Ok(()),
+            invalid => Err(invalid.join("; ")),
+        });
+
+        match result {
+            Ok(()) => game = candidate,
+            Err(err) => problems.push(format!("{layer}: {err}")),
+        }
+    };
+
+    if let Some((label, preset)) = preset {
+        apply(format!("{label} preset"), &|cfg| {
+            preset.apply(cfg);
+            Ok(())
+        });
+    }
+
+    for config_override in overrides {
+        apply(
+            format!("--set {}={}", config_override.key, config_override.value),
+            &|cfg| config_override.apply(cfg),
+        );
+    }
+
+    (game, problems)
+}
+
+fn save_difficulty_system(difficulty: Res<Difficulty>) {
+    storage::save(DIFFICULTY_KEY, &*difficulty);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::{parse_config, Format};
+
+    #[test]
+    fn shipped_presets_are_valid() {
+        let bytes = std::fs::read("assets/config/difficulty.presets.json").unwrap();
+
+        match parse_config::<DifficultyPresets>(&bytes, Format::Json) {
+            Ok(parsed) => {
+                assert_eq!(parsed.unknown_keys, Vec::<String>::new());
+                // otherwise it would hide edits to the base config
+                assert_eq!(parsed.config.normal, Preset::default());
+            }
+            Err(err) => panic!("{err}"),
+        }
+    }
+
+    #[test]
+    fn presets_only_override_their_fields() {
+        let base = GameConfig {
+            gravity: -10.,
+            ..default()
+        };
+        let preset = Preset {
+            player_health: Some(7),
+            ..default()
+        };
+
+        let mut cfg = base.clone();
+        preset.apply(&mut cfg);
+
+        assert_eq!(
+            cfg,
+            GameConfig {
+                player_health: 7,
+                ..base
+            }
+        );
+        assert!(preset.sets("player_health"));
+        assert!(!preset.sets("enemy_min_speed"));
+    }
+
+    #[test]
+    fn overrides_win_and_invalid_layers_are_left_out() {
+        let preset = Preset {
+            player_health: Some(7),
+            enemy_min_speed: Some(150.),
+            ..default()
+        };
+        let overrides = [
+            "player_health=2".parse().unwrap(),
+            "enemy_respawn_min_delay=9".parse().unwrap(),
+        ];
+
+        let base = GameConfig {
+            enemy_max_speed: 200.,
+            enemy_respawn_max_delay: 10.,
+            ..default()
+        };
+        let (cfg, problems) = effective_config(&base, Some(("Hard", &preset)), &overrides);
+        assert_eq!(cfg.player_health, 2);
+        assert_eq!(cfg.enemy_min_speed, 150.);
+        assert_eq!(problems, Vec::<String>::new());
+
+        let base = GameConfig::default();
+        let (cfg, problems) = effective_config(&base, Some(("Hard", &preset)), &overrides);
+        assert_eq!(
+            cfg,
+            GameConfig {
+                player_health: 2,
+                ..base
+            }
+        );
+        assert_eq!(
+            problems,
+            [
+                "Hard preset: enemy_min_speed (150) must not be above enemy_max_speed (100)",
+                "--set enemy_respawn_min_delay=9: \
+                 enemy_respawn_min_delay (9) must not be above enemy_respawn_max_delay (4)",
+            ]
+        );
+    }
+
+    #[test]
+    fn presets_are_checked_without_a_base() {
+        let preset = Preset {
+            player_health: Some(0),
+            enemy_respawn_min_delay: Some(3.),
+            enemy_respawn_max_delay: Some(2.),
+            // fine here, whatever the base's max speed is
+            enemy_min_speed: Some(500.),
+            ..default()
+        };
+
+        assert_eq!(
+            preset.validate(),
+            [
+                "player_health must be at least 1",
+                "enemy_respawn_min_delay (3) must not be above enemy_respawn_max_delay (2)",
+            ]
+        );
+    }
+}
@@ -1,16 +1,24 @@
-use bevy::prelude::*;
+use bevy::{prelude::*, sprite::Anchor};
 use rand::Rng;
 
 use crate::{
     animation::{Animation, AnimationDir},
     asset_loader::ImageAssets,
+    collider::Collider,
     config::Config,
     game_state::GameState,
-    health::Health,
-    physics::{Airborne, Radius, Velocity},
+    health::{EnemyKilled, Health},
+    layers::CollisionLayers,
+    physics::{
+        Anchored, CollisionSystems, Interpolated, Mass, MovementSystems, PhysicsBody, PlayerHit,
+        Stunned, Velocity,
+    },
+    player::Player,
+    replay::{GameRng, RunClock},
 };
 
 #[derive(Component, Default, Debug)]
+#[require(CollisionLayers = CollisionLayers::ENEMY)]
 pub struct Enemy;
 
 #[derive(Component, Default, Debug)]
@@ -20,9 +28,9 @@ pub struct EnemyMovement {
 }
 
 impl EnemyMovement {
-    pub fn new_random(rng: &mut impl Rng) -> Self {
+    pub fn new_random(rng: &mut impl Rng, cfg: &Config) -> Self {
         let period = rng.random_range(4.0..8.0);
-        let speed = rng.random_range(80.0..100.0);
+        let speed = rng.random_range(cfg.game.enemy_min_speed..=cfg.game.enemy_max_speed);
         Self { period, speed }
     }
 }
@@ -49,6 +57,60 @@ impl EnemyWobble {
     }
 }
 
+#[derive(Component, Default, Debug)]
+#[require(Anchored)]
+pub struct Turret {
+    pub aim: f32,
+    reload: Timer,
+    burst: Timer,
+    shots_left: u32,
+}
+
+impl Turret {
+    pub fn new(reload_secs: f32, burst_interval_secs: f32) -> Self {
+        Self {
+            aim: 0.,
+            reload: Timer::from_seconds(reload_secs, TimerMode::Repeating),
+            burst: Timer::from_seconds(burst_interval_secs, TimerMode::Repeating),
+            shots_left: 0,
+        }
+    }
+
+    /// On top, where a dash can reach it. Anywhere lower, the body or the
+    /// floor is always in the way.
+    pub fn weak_spot() -> WeakSpot {
+        WeakSpot::new(WeakSpotLocation::North, 16.)
+    }
+
+    pub fn aim_dir(&self) -> Vec2 {
+        Vec2::from_angle(self.aim).rotate(Vec2::Y)
+    }
+}
+
+#[derive(Component, Default, Debug)]
+pub struct TurretBarrel;
+
+#[derive(Component, Default, Debug)]
+#[require(Interpolated, CollisionLayers = CollisionLayers::ENEMY_PROJECTILE)]
+pub struct EnemyProjectile {
+    pub vel: Vec2,
+    pub timer: Timer,
+}
+
+impl EnemyProjectile {
+    pub fn new(vel: Vec2, duration_secs: f32) -> Self {
+        Self {
+            vel,
+            timer: Timer::from_seconds(duration_secs, TimerMode::Once),
+        }
+    }
+}
+
+#[derive(Component, Default, Debug)]
+pub struct DeathBurst {
+    pub timer: Timer,
+}
+
 #[derive(Default, Debug)]
 pub enum WeakSpotLocation {
     North,
@@ -102,10 +164,10 @@ impl WeakSpotLocation {
         }
     }
 
-    pub fn to_rotation(&self) -> Quat {
+    pub fn to_angle(&self) -> f32 {
         use std::f32::consts::FRAC_PI_2;
 
-        let angle = match self {
+        match self {
             Self::North => 0.0,
             Self::East => -FRAC_PI_2,
             Self::South => std::f32::consts::PI,
@@ -114,9 +176,7 @@ impl WeakSpotLocation {
             Self::_SouthEast => -FRAC_PI_2 / 2.0,
             Self::_NorthWest => FRAC_PI_2 * 1.5,
             Self::_SouthWest => FRAC_PI_2 / 2.0,
-        };
-
-        Quat::from_rotation_z(angle)
+        }
     }
 
     pub fn to_size(&self, side: f32) -> Vec2 {
@@ -141,43 +201,39 @@ impl WeakSpotLocation {
 pub struct WeakSpot {
     pub location: WeakSpotLocation,
     pub size: Vec2,
-    pub rotation: Quat,
 }
 
 impl WeakSpot {
     pub fn new(location: WeakSpotLocation, side: f32) -> Self {
         let size = location.to_size(side);
-        let rotation = location.to_rotation();
-        Self {
-            location,
-            size,
-            rotation,
-        }
+        Self { location, size }
     }
 
     pub fn new_ortho(rng: &mut impl Rng, side: f32) -> Self {
         let location = WeakSpotLocation::new_ortho(rng);
         let size = location.to_size(side);
-        let rotation = location.to_rotation();
-        Self {
-            location,
-            size,
-            rotation,
-        }
+        Self { location, size }
+    }
+
+    /// Shape and offset from the owner's center of the weak spot on a body of
+    /// `body_radius`.
+    pub fn collider(&self, body_radius: f32) -> (Collider, Vec2) {
+        let collider = Collider::obb(self.size / 2.0, self.location.to_angle());
+        let offset = self.location.to_dir() * body_radius;
+        (collider, offset)
     }
 
     pub fn _new_random(rng: &mut impl Rng, side: f32) -> Self {
         let location = WeakSpotLocation::_new_random(rng);
         let size = location.to_size(side);
-        let rotation = location.to_rotation();
-        Self {
-            location,
-            size,
-            rotation,
-        }
+        Self { location, size }
     }
 }
 
+/// Rotor and arm layers in `enemies/drone.png`, one row per layer.
+const DRONE_PART_ROWS: usize = 4;
+const DRONE_PART_FRAMES: usize = 4;
+
 #[derive(Resource, Default)]
 struct EnemyRespawnTimer {
     timer: Option<Timer>,
@@ -190,13 +246,29 @@ impl Plugin for EnemyPlugin {
         app.insert_resource(EnemyRespawnTimer::default())
             .add_systems(OnEnter(GameState::Running), spawn_enemies)
             .add_systems(
-                Update,
+                FixedUpdate,
                 (
                     enemy_movement_system,
                     enemy_wobble_system,
+                    turret_aim_system,
+                    turret_barrel_system,
+                    turret_fire_system,
+                    enemy_projectile_system,
                     enemy_respawn_system,
+                    death_burst_system,
                 )
+                    // fixed order, so they draw from `GameRng` in the same order every run
+                    .chain()
+                    .in_set(MovementSystems::Enemies)
                     .run_if(in_state(GameState::Running)),
+            )
+            .add_systems(
+                FixedUpdate,
+                enemy_projectile_hit_system.in_set(CollisionSystems::Resolve),
+            )
+            .add_systems(
+                FixedUpdate,
+                enemy_death_burst_system.in_set(CollisionSystems::React),
             );
     }
 }
@@ -204,15 +276,24 @@ impl Plugin for EnemyPlugin {
 fn spawn_enemies(
     mut commands: Commands,
     enemies: Query<Entity, With<Enemy>>,
+    projectiles: Query<Entity, With<EnemyProjectile>>,
     image_assets: Res<ImageAssets>,
+    mut respawn_state: ResMut<EnemyRespawnTimer>,
+    mut rng: ResMut<GameRng>,
     cfg: Res<Config>,
 ) {
+    respawn_state.timer = None;
+
     for enemy in &enemies {
         commands.entity(enemy).despawn();
     }
 
-    for _ in 0..3 {
-        spawn_enemy(&mut commands, &image_assets, &cfg);
+    for projectile in &projectiles {
+        commands.entity(projectile).despawn();
+    }
+
+    for _ in 0..cfg.game.enemy_respawn_threshold {
+        spawn_enemy(&mut commands, &image_assets, &mut rng.0, &cfg);
     }
 }
 
@@ -221,70 +302,65 @@ fn enemy_respawn_system(
     image_assets: Res<ImageAssets>,
     enemies: Query<(), With<Enemy>>,
     mut respawn_state: ResMut<EnemyRespawnTimer>,
+    mut rng: ResMut<GameRng>,
     time: Res<Time>,
     cfg: Res<Config>,
 ) {
-    if enemies.iter().count() > 3 {
+    if enemies.iter().count() > cfg.game.enemy_respawn_threshold as usize {
         respawn_state.timer = None;
         return;
     }
 
     if respawn_state.timer.is_none() {
-        let mut rng = rand::rng();
-        let duration = rng.random_range(2.0..4.0);
+        let duration =
+            rng.random_range(cfg.game.enemy_respawn_min_delay..=cfg.game.enemy_respawn_max_delay);
         respawn_state.timer = Some(Timer::from_seconds(duration, TimerMode::Once));
     }
 
     if let Some(timer) = respawn_state.timer.as_mut() {
         timer.tick(time.delta());
         if timer.finished() {
-            let mut rng = rand::rng();
             let n = rng.random_range(1..=3);
             for _ in 0..n {
-                spawn_enemy(&mut commands, &image_assets, &cfg);
+                spawn_enemy(&mut commands, &image_assets, &mut rng.0, &cfg);
             }
         }
     }
 }
 
-fn spawn_enemy(commands: &mut Commands, image_assets: &Res<ImageAssets>, cfg: &Config) {
-    let mut rng = rand::rng();
-
+fn spawn_enemy(
+    commands: &mut Commands,
+    image_assets: &Res<ImageAssets>,
+    rng: &mut impl Rng,
+    cfg: &Config,
+) {
     let is_ground_enemy = rng.random_bool(0.3);
 
     if is_ground_enemy {
-        spawn_ground_enemy(commands, image_assets, &mut rng, cfg);
+        spawn_ground_enemy(commands, image_assets, rng, cfg);
     } else {
-        spawn_fly_enemy(commands, image_assets, &mut rng);
+        spawn_fly_enemy(commands, image_assets, rng, cfg);
     }
 }
 
-fn spawn_fly_enemy(commands: &mut Commands, image_assets: &Res<ImageAssets>, rng: &mut impl Rng) {
+fn spawn_fly_enemy(
+    commands: &mut Commands,
+    image_assets: &Res<ImageAssets>,
+    rng: &mut impl Rng,
+    cfg: &Config,
+) {
     let x = rng.random_range(-150.0..150.0);
     let y = rng.random_range(280.0..360.0);
     let position = Vec3::new(x, y, 0.);
 
     let weak_spot = WeakSpot::new_ortho(rng, 16.);
-    let movement = EnemyMovement::new_random(rng);
+    let movement = EnemyMovement::new_random(rng, cfg);
     let wobble = EnemyWobble::new_random(rng);
 
     let layout = image_assets.enemy_drone_layout.clone();
     let image = image_assets.enemy_drone.clone();
 
-    let animation = Animation {
-        first: 0,
-        last: 0,
-        dir: AnimationDir::Forwards,
-        timer: Timer::from_seconds(0.1, TimerMode::Repeating),
-    };
-
-    let sprite = Sprite::from_atlas_image(
-        image,
-        TextureAtlas {
-            layout,
-            index: animation.first,
-        },
-    );
+    let sprite = Sprite::from_atlas_image(image, TextureAtlas { layout, index: 0 });
 
     let weak_layout = image_assets.enemy_drone_layout.clone();
     let weak_image = image_assets.enemy_drone.clone();
@@ -302,12 +378,16 @@ fn spawn_fly_enemy(commands: &mut Commands, image_assets: &Res<ImageAssets>, rng
     let mut entity = commands.spawn((
         Enemy,
         Velocity::default(),
+        Mass(2.),
         Transform::from_translation(position),
-        Radius(16.),
+        Collider::circle(16.),
         Health::new(1),
         movement,
         wobble,
-        Airborne,
+        PhysicsBody {
+            gravity_scale: 0.,
+            ..default()
+        },
         weak_spot,
         sprite,
     ));
@@ -317,6 +397,29 @@ fn spawn_fly_enemy(commands: &mut Commands, image_assets: &Res<ImageAssets>, rng
             Transform::from_translation(Vec3::new(0., 0., 1.)),
             weak_sprite,
         ));
+
+        for row in 0..DRONE_PART_ROWS {
+            let animation = Animation {
+                first: row * DRONE_PART_FRAMES,
+                last: row * DRONE_PART_FRAMES + DRONE_PART_FRAMES - 1,
+                dir: AnimationDir::Forwards,
+                timer: Timer::from_seconds(0.08, TimerMode::Repeating),
+            };
+
+            let part_sprite = Sprite::from_atlas_image(
+                image_assets.enemy_drone_parts.clone(),
+                TextureAtlas {
+                    layout: image_assets.enemy_drone_parts_layout.clone(),
+                    index: animation.first,
+                },
+            );
+
+            parent.spawn((
+                Transform::from_translation(Vec3::new(0., 0., -1.)),
+                part_sprite,
+                animation,
+            ));
+        }
     });
 }
 
@@ -330,26 +433,16 @@ fn spawn_ground_enemy(
     let y = cfg.game.floor_y + 16.;
     let position = Vec3::new(x, y, 0.);
 
-    let weak_spot = WeakSpot::new(WeakSpotLocation::South, 16.);
-    let movement = EnemyMovement::new_random(rng);
+    let weak_spot = Turret::weak_spot();
+    let turret = Turret::new(
+        cfg.game.turret_reload_duration,
+        cfg.game.turret_burst_interval,
+    );
 
     let layout = image_assets.enemy_drone_layout.clone();
     let image = image_assets.enemy_drone.clone();
 
-    let animation = Animation {
-        first: 0,
-        last: 0,
-        dir: AnimationDir::Forwards,
-        timer: Timer::from_seconds(0.1, TimerMode::Repeating),
-    };
-
-    let sprite = Sprite::from_atlas_image(
-        image,
-        TextureAtlas {
-            layout,
-            index: animation.first,
-        },
-    );
+    let sprite = Sprite::from_atlas_image(image, TextureAtlas { layout, index: 0 });
 
     let weak_layout = image_assets.enemy_drone_layout.clone();
     let weak_image = image_assets.enemy_drone.clone();
@@ -364,13 +457,25 @@ fn spawn_ground_enemy(
         ..default()
     };
 
+    let barrel_sprite = Sprite {
+        color: Color::srgb_u8(200, 200, 10),
+        custom_size: Some(Vec2::new(4., 20.)),
+        anchor: Anchor::BottomCenter,
+        ..default()
+    };
+
     let mut entity = commands.spawn((
         Enemy,
         Velocity::default(),
+        Mass(20.),
         Transform::from_translation(position),
-        Radius(16.),
+        Collider::circle(16.),
         Health::new(1),
-        movement,
+        PhysicsBody {
+            gravity_scale: 0.,
+            ..default()
+        },
+        turret,
         weak_spot,
         sprite,
     ));
@@ -380,14 +485,19 @@ fn spawn_ground_enemy(
             Transform::from_translation(Vec3::new(0., 0., 1.)),
             weak_sprite,
         ));
+        parent.spawn((
+            TurretBarrel,
+            Transform::from_translation(Vec3::new(0., 0., -1.)),
+            barrel_sprite,
+        ));
     });
 }
 
 fn enemy_movement_system(
-    mut enemies: Query<(&mut Velocity, &EnemyMovement), With<Enemy>>,
-    time: Res<Time>,
+    mut enemies: Query<(&mut Velocity, &EnemyMovement), (With<Enemy>, Without<Stunned>)>,
+    clock: Res<RunClock>,
 ) {
-    let timer = time.elapsed_secs();
+    let timer = clock.elapsed;
 
     for (mut vel, movement) in enemies.iter_mut() {
         let period = movement.period;
@@ -412,11 +522,14 @@ fn ease_in_out(t: f32) -> f32 {
 }
 
 fn enemy_wobble_system(
-    mut enemies: Query<(&mut Velocity, &mut EnemyWobble, &Transform, &EnemyMovement), With<Enemy>>,
+    mut enemies: Query<
+        (&mut Velocity, &mut EnemyWobble, &Transform, &EnemyMovement),
+        (With<Enemy>, Without<Stunned>),
+    >,
+    mut rng: ResMut<GameRng>,
     time: Res<Time>,
 ) {
     let dt = time.delta();
-    let mut rng = rand::rng();
 
     for (mut vel, mut wobble, transform, movement) in enemies.iter_mut() {
         wobble.timer.tick(dt);
@@ -433,3 +546,164 @@ fn enemy_wobble_system(
         }
     }
 }
+
+fn turret_aim_system(
+    mut turrets: Query<(&mut Turret, &Transform), With<Enemy>>,
+    player: Query<&Transform, With<Player>>,
+    time: Res<Time>,
+    cfg: Res<Config>,
+) {
+    let dt = time.delta_secs();
+
+    let Ok(player_transform) = player.single() else {
+        return;
+    };
+
+    let player_pos = player_transform.translation.xy();
+
+    for (mut turret, transform) in turrets.iter_mut() {
+        let to_player = player_pos - transform.translation.xy();
+        if to_player == Vec2::ZERO {
+            continue;
+        }
+
+        let max_angle = cfg.game.turret_max_aim_angle;
+        let target = Vec2::Y.angle_to(to_player).clamp(-max_angle, max_angle);
+
+        let max_step = cfg.game.turret_turn_speed * dt;
+        let step = (target - turret.aim).clamp(-max_step, max_step);
+
+        turret.aim += step;
+    }
+}
+
+fn turret_barrel_system(
+    turrets: Query<(&Turret, &Children)>,
+    mut barrels: Query<&mut Transform, With<TurretBarrel>>,
+) {
+    for (turret, children) in turrets.iter() {
+        for &child in children.into_iter() {
+            if let Ok(mut transform) = barrels.get_mut(child) {
+                transform.rotation = Quat::from_rotation_z(turret.aim);
+            }
+        }
+    }
+}
+
+fn turret_fire_system(
+    mut commands: Commands,
+    mut turrets: Query<(&mut Turret, &Transform), With<Enemy>>,
+    time: Res<Time>,
+    cfg: Res<Config>,
+) {
+    for (mut turret, transform) in turrets.iter_mut() {
+        if turret.shots_left == 0 {
+            turret.reload.tick(time.delta());
+            if turret.reload.just_finished() {
+                turret.shots_left = cfg.game.turret_burst_count;
+                turret.burst.reset();
+                spawn_enemy_projectile(&mut commands, transform, &turret, &cfg);
+                turret.shots_left = turret.shots_left.saturating_sub(1);
+            }
+            continue;
+        }
+
+        turret.burst.tick(time.delta());
+        if turret.burst.just_finished() {
+            spawn_enemy_projectile(&mut commands, transform, &turret, &cfg);
+            turret.shots_left = turret.shots_left.saturating_sub(1);
+        }
+    }
+}
+
+fn spawn_enemy_projectile(
+    commands: &mut Commands,
+    transform: &Transform,
+    turret: &Turret,
+    cfg: &Config,
+) {
+    let dir = turret.aim_dir();
+    let position = transform.translation + (dir * 20.).extend(0.);
+
+    let sprite = Sprite {
+        color: Color::srgb_u8(200, 10, 10),
+        custom_size: Some(Vec2::new(6., 12.)),
+        ..default()
+    };
+
+    commands.spawn((
+        EnemyProjectile::new(
+            dir * cfg.game.turret_projectile_speed,
+            cfg.game.turret_projectile_lifetime,
+        ),
+        Transform::from_translation(position).with_rotation(Quat::from_rotation_z(turret.aim)),
+        Collider::capsule(3., 3., turret.aim),
+        sprite,
+    ));
+}
+
+fn enemy_projectile_hit_system(
+    mut commands: Commands,
+    mut player_hits: EventReader<PlayerHit>,
+    projectiles: Query<(), With<EnemyProjectile>>,
+) {
+    for hit in player_hits.read() {
+        if projectiles.contains(hit.source) {
+            // it may also expire this tick, or be hit twice
+            commands.entity(hit.source).try_despawn();
+        }
+    }
+}
+
+fn enemy_projectile_system(
+    mut commands: Commands,
+    mut projectiles: Query<(Entity, &mut EnemyProjectile, &mut Transform)>,
+    time: Res<Time>,
+) {
+    let dt = time.delta_secs();
+
+    for (entity, mut projectile, mut transform) in projectiles.iter_mut() {
+        transform.translation += (projectile.vel * dt).extend(0.);
+
+        projectile.timer.tick(time.delta());
+        if projectile.timer.finished() {
+            commands.entity(entity).try_despawn();
+        }
+    }
+}
+
+fn enemy_death_burst_system(mut commands: Commands, mut enemy_killed: EventReader<EnemyKilled>) {
+    for kill in enemy_killed.read() {
+        let sprite = Sprite {
+            color: Color::srgb_u8(200, 200, 10),
+            custom_size: Some(Vec2::splat(32.)),
+            ..default()
+        };
+
+        commands.spawn((
+            DeathBurst {
+                timer: Timer::from_seconds(0.25, TimerMode::Once),
+            },
+            Transform::from_translation(kill.position.extend(2.)),
+            sprite,
+        ));
+    }
+}
+
+fn death_burst_system(
+    mut commands: Commands,
+    mut bursts: Query<(Entity, &mut DeathBurst, &mut Sprite, &mut Transform)>,
+    time: Res<Time>,
+) {
+    for (entity, mut burst, mut sprite, mut transform) in bursts.iter_mut() {
+        burst.timer.tick(time.delta());
+
+        let remaining = burst.timer.remaining_secs() / burst.timer.duration().as_secs_f32();
+        sprite.color.set_alpha(remaining);
+        transform.scale = Vec3::splat(2. - remaining);
+
+        if burst.timer.finished() {
+            commands.entity(entity).despawn();
+        }
+    }
+}
@@ -6,6 +6,8 @@ pub enum GameState {
     AssetLoading,
     Setup,
     MainMenu,
+    Controls,
+    HighScores,
     Running,
     GameOver,
 }
@@ -1,5 +1,13 @@
 use bevy::prelude::*;
 
+use crate::{
+    config::Config,
+    enemy::Enemy,
+    game_state::GameState,
+    physics::{CollisionImmunity, CollisionSystems, EnemyWeakSpotHit, NukeHit, PlayerHit},
+    player::Player,
+};
+
 #[derive(Component, Default, Debug)]
 pub struct Health {
     pub current: u8,
@@ -18,8 +26,124 @@ impl Health {
 #[derive(Component, Default, Debug)]
 pub struct Killed;
 
+/// An enemy ran out of health. `killer` is the player or nuke that finished it.
+#[derive(Event, Debug)]
+pub struct EnemyKilled {
+    pub killer: Entity,
+    pub position: Vec2,
+}
+
+/// The player lost a point of health.
+#[derive(Event, Debug)]
+pub struct PlayerDamaged;
+
+#[derive(Event, Debug)]
+pub struct PlayerDied;
+
 pub struct HealthPlugin;
 
 impl Plugin for HealthPlugin {
-    fn build(&self, _app: &mut App) {}
+    fn build(&self, app: &mut App) {
+        app.add_event::<EnemyKilled>()
+            .add_event::<PlayerDamaged>()
+            .add_event::<PlayerDied>()
+            .add_systems(
+                FixedUpdate,
+                (player_damage_system, enemy_damage_system)
+                    .chain()
+                    .in_set(CollisionSystems::Resolve),
+            )
+            .add_systems(
+                FixedUpdate,
+                game_over_system.in_set(CollisionSystems::React),
+            )
+            .add_systems(FixedPostUpdate, despawn_killed_system);
+    }
+}
+
+fn player_damage_system(
+    mut commands: Commands,
+    mut player_hits: EventReader<PlayerHit>,
+    mut player_damaged: EventWriter<PlayerDamaged>,
+    mut player_died: EventWriter<PlayerDied>,
+    mut players: Query<&mut Health, (With<Player>, Without<CollisionImmunity>)>,
+    cfg: Res<Config>,
+) {
+    let mut damaged = Vec::new();
+
+    for hit in player_hits.read() {
+        if damaged.contains(&hit.player) {
+            continue;
+        }
+
+        let Ok(mut health) = players.get_mut(hit.player) else {
+            continue;
+        };
+
+        damaged.push(hit.player);
+
+        if health.current == 0 {
+            continue;
+        }
+
+        health.current -= 1;
+        player_damaged.write(PlayerDamaged);
+
+        if health.current == 0 {
+            player_died.write(PlayerDied);
+        } else {
+            commands.entity(hit.player).insert(CollisionImmunity::new(
+                cfg.game.player_hit_immunity_duration,
+            ));
+        }
+    }
+}
+
+pub fn enemy_damage_system(
+    mut commands: Commands,
+    mut weak_spot_hits: EventReader<EnemyWeakSpotHit>,
+    mut nuke_hits: EventReader<NukeHit>,
+    mut enemy_killed: EventWriter<EnemyKilled>,
+    mut enemies: Query<(&mut Health, &Transform), (With<Enemy>, Without<Killed>)>,
+) {
+    let hits = weak_spot_hits
+        .read()
+        .map(|hit| (hit.enemy, hit.player))
+        .chain(nuke_hits.read().map(|hit| (hit.enemy, hit.nuke)));
+
+    let mut killed = Vec::new();
+
+    for (enemy, source) in hits {
+        if killed.contains(&enemy) {
+            continue;
+        }
+
+        let Ok((mut health, transform)) = enemies.get_mut(enemy) else {
+            continue;
+        };
+
+        health.current = health.current.saturating_sub(1);
+
+        if health.current == 0 {
+            killed.push(enemy);
+            commands.entity(enemy).insert(Killed);
+
+            enemy_killed.write(EnemyKilled {
+                killer: source,
+                position: transform.translation.truncate(),
+            });
+        }
+    }
+}
+
+fn game_over_system(mut commands: Commands, mut player_died: EventReader<PlayerDied>) {
+    if player_died.read().next().is_some() {
+        commands.set_state(GameState::GameOver);
+    }
+}
+
+fn despawn_killed_system(mut commands: Commands, killed: Query<Entity, With<Killed>>) {
+    for entity in killed.iter() {
+        commands.entity(entity).despawn();
+    }
 }
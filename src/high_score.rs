@@ -0,0 +1,171 @@
+use std::cmp::Reverse;
+
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::{difficulty::Difficulty, storage};
+
+const HIGH_SCORES_KEY: &str = "high_scores";
+/// Entries kept per difficulty.
+pub const MAX_ENTRIES: usize = 10;
+pub const NAME_LENGTH: usize = 3;
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct HighScore {
+    pub name: String,
+    pub score: u32,
+    /// `YYYY-MM-DD`, UTC.
+    pub date: String,
+    pub difficulty: Difficulty,
+    pub seed: u64,
+}
+
+/// Best runs, ranked separately for each difficulty since their scores aren't
+/// comparable.
+#[derive(Resource, Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct HighScores(Vec<HighScore>);
+
+pub struct HighScorePlugin;
+
+impl Plugin for HighScorePlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(HighScores::load());
+    }
+}
+
+impl HighScores {
+    /// Saved scores, or an empty table if there are none or they can't be read.
+    pub fn load() -> Self {
+        let mut scores = storage::load::<HighScores>(HIGH_SCORES_KEY).unwrap_or_default();
+        scores.tidy();
+        scores
+    }
+
+    pub fn save(&self) {
+        storage::save(HIGH_SCORES_KEY, self);
+    }
+
+    /// Best first.
+    pub fn top(&self, difficulty: Difficulty) -> impl Iterator<Item = &HighScore> {
+        self.0
+            .iter()
+            .filter(move |entry| entry.difficulty == difficulty)
+    }
+
+    /// Whether `score` would make it onto the table. Ties with the last entry
+    /// don't.
+    pub fn qualifies(&self, score: u32, difficulty: Difficulty) -> bool {
+        score > 0
+            && self
+                .top(difficulty)
+                .nth(MAX_ENTRIES - 1)
+                .is_none_or(|last| score > last.score)
+    }
+
+    pub fn insert(&mut self, entry: HighScore) {
+        self.0.push(entry);
+        self.tidy();
+    }
+
+    /// Sorts, trims each difficulty to [`MAX_ENTRIES`] and cleans up names,
+    /// which may come from a hand-edited save.
+    fn tidy(&mut self) {
+        for entry in &mut self.0 {
+            entry.name = sanitize_name(&entry.name);
+        }
+
+        // stable, so earlier entries win ties
+        self.0.sort_by_key(|entry| Reverse(entry.score));
+
+        let mut counts = [0; Difficulty::ALL.len()];
+        self.0.retain(|entry| {
+            let count = &mut counts[entry.difficulty as usize];
+            *count += 1;
+            *count <= MAX_ENTRIES
+        });
+    }
+}
+
+/// Up to [`NAME_LENGTH`] uppercase ASCII letters.
+pub fn sanitize_name(name: &str) -> String {
+    name.chars()
+        .filter(char::is_ascii_alphabetic)
+        .map(|c| c.to_ascii_uppercase())
+        .take(NAME_LENGTH)
+        .collect()
+}
+
+/// Today's date as `YYYY-MM-DD`, UTC.
+pub fn today() -> String {
+    #[cfg(not(target_arch = "wasm32"))]
+    let millis = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map_or(0, |elapsed| elapsed.as_millis() as i64);
+    #[cfg(target_arch = "wasm32")]
+    let millis = js_sys::Date::now() as i64;
+
+    let (year, month, day) = civil_from_days(millis.div_euclid(86_400_000));
+    format!("{year:04}-{month:02}-{day:02}")
+}
+
+/// Days since 1970-01-01 to a proleptic Gregorian date, after Howard Hinnant's
+/// `civil_from_days`.
+fn civil_from_days(days: i64) -> (i64, u32, u32) {
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let day_of_era = z.rem_euclid(146_097);
+    let year_of_era =
+        (day_of_era - day_of_era / 1460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let mp = (5 * day_of_year + 2) / 153;
+    let day = (day_of_year - (153 * mp + 2) / 5 + 1) as u32;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 } as u32;
+    let year = year_of_era + era * 400 + i64::from(month <= 2);
+
+    (year, month, day)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(score: u32, difficulty: Difficulty) -> HighScore {
+        HighScore {
+            name: "abc1d".to_string(),
+            score,
+            date: "2025-01-01".to_string(),
+            difficulty,
+            seed: 0,
+        }
+    }
+
+    #[test]
+    fn keeps_the_best_ten_per_difficulty() {
+        let mut scores = HighScores::default();
+        for score in 1..=12 {
+            scores.insert(entry(score, Difficulty::Normal));
+        }
+        scores.insert(entry(1, Difficulty::Hard));
+
+        let normal: Vec<_> = scores
+            .top(Difficulty::Normal)
+            .map(|entry| entry.score)
+            .collect();
+        assert_eq!(normal, (3..=12).rev().collect::<Vec<_>>());
+        assert_eq!(scores.top(Difficulty::Hard).count(), 1);
+        assert_eq!(scores.top(Difficulty::Normal).next().unwrap().name, "ABC");
+
+        assert!(!scores.qualifies(3, Difficulty::Normal));
+        assert!(scores.qualifies(4, Difficulty::Normal));
+        assert!(scores.qualifies(1, Difficulty::Easy));
+        assert!(!scores.qualifies(0, Difficulty::Easy));
+    }
+
+    #[test]
+    fn dates_count_from_the_unix_epoch() {
+        assert_eq!(civil_from_days(0), (1970, 1, 1));
+        assert_eq!(civil_from_days(-1), (1969, 12, 31));
+        assert_eq!(civil_from_days(11_016), (2000, 2, 29));
+        assert_eq!(civil_from_days(20_454), (2026, 1, 1));
+    }
+}
@@ -1,95 +1,350 @@
+use std::{collections::BTreeMap, fmt, mem};
+
 use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::{game_state::GameState, replay::is_playing_back, storage, touch::TouchControls};
+
+const BINDINGS_KEY: &str = "bindings";
+/// Stick deflection below which analog input is ignored.
+const STICK_DEADZONE: f32 = 0.2;
+
+/// Something the player can do, independent of the device doing it.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub enum Action {
+    Up,
+    Down,
+    Left,
+    Right,
+    Dash,
+}
+
+impl Action {
+    pub const ALL: [Action; 5] = [
+        Action::Up,
+        Action::Down,
+        Action::Left,
+        Action::Right,
+        Action::Dash,
+    ];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Action::Up => "Up",
+            Action::Down => "Down",
+            Action::Left => "Left",
+            Action::Right => "Right",
+            Action::Dash => "Dash",
+        }
+    }
+
+    fn bit(self) -> u8 {
+        1 << self as u8
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Binding {
+    Key(KeyCode),
+    Mouse(MouseButton),
+    Gamepad(GamepadButton),
+}
+
+impl Binding {
+    fn pressed(
+        self,
+        keys: &ButtonInput<KeyCode>,
+        mouse: &ButtonInput<MouseButton>,
+        gamepads: &Query<&Gamepad>,
+    ) -> bool {
+        match self {
+            Binding::Key(key) => keys.pressed(key),
+            Binding::Mouse(button) => mouse.pressed(button),
+            Binding::Gamepad(button) => gamepads.iter().any(|gamepad| gamepad.pressed(button)),
+        }
+    }
+
+    /// The first binding pressed this frame on any device, used when rebinding.
+    pub fn just_pressed(
+        keys: &ButtonInput<KeyCode>,
+        mouse: &ButtonInput<MouseButton>,
+        gamepads: &Query<&Gamepad>,
+    ) -> Option<Binding> {
+        keys.get_just_pressed()
+            .next()
+            .map(|key| Binding::Key(*key))
+            .or_else(|| {
+                mouse
+                    .get_just_pressed()
+                    .next()
+                    .map(|button| Binding::Mouse(*button))
+            })
+            .or_else(|| {
+                gamepads.iter().find_map(|gamepad| {
+                    gamepad
+                        .get_just_pressed()
+                        .next()
+                        .map(|button| Binding::Gamepad(*button))
+                })
+            })
+    }
+}
+
+impl fmt::Display for Binding {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Binding::Key(key) => {
+                let name = format!("{key:?}");
+                let name = name
+                    .strip_prefix("Key")
+                    .or_else(|| name.strip_prefix("Digit"))
+                    .unwrap_or(&name);
+                write!(f, "{name}")
+            }
+            Binding::Mouse(button) => write!(f, "Mouse {button:?}"),
+            Binding::Gamepad(button) => write!(f, "Pad {button:?}"),
+        }
+    }
+}
+
+/// Bindings for every action. Persisted whenever it is rebound.
+#[derive(Resource, Clone, Debug, Serialize, Deserialize)]
+pub struct ActionMap(BTreeMap<Action, Vec<Binding>>);
+
+impl Default for ActionMap {
+    fn default() -> Self {
+        use Binding::*;
+
+        Self(BTreeMap::from([
+            (
+                Action::Up,
+                vec![
+                    Key(KeyCode::KeyW),
+                    Key(KeyCode::ArrowUp),
+                    Gamepad(GamepadButton::DPadUp),
+                ],
+            ),
+            (
+                Action::Down,
+                vec![
+                    Key(KeyCode::KeyS),
+                    Key(KeyCode::ArrowDown),
+                    Gamepad(GamepadButton::DPadDown),
+                ],
+            ),
+            (
+                Action::Left,
+                vec![
+                    Key(KeyCode::KeyA),
+                    Key(KeyCode::ArrowLeft),
+                    Gamepad(GamepadButton::DPadLeft),
+                ],
+            ),
+            (
+                Action::Right,
+                vec![
+                    Key(KeyCode::KeyD),
+                    Key(KeyCode::ArrowRight),
+                    Gamepad(GamepadButton::DPadRight),
+                ],
+            ),
+            (
+                Action::Dash,
+                vec![
+                    Mouse(MouseButton::Left),
+                    Gamepad(GamepadButton::RightTrigger2),
+                ],
+            ),
+        ]))
+    }
+}
+
+impl ActionMap {
+    /// Saved bindings, with defaults for any action missing from the save.
+    pub fn load() -> Self {
+        let mut map = Self::default();
+
+        if let Some(saved) = storage::load::<ActionMap>(BINDINGS_KEY) {
+            map.0.extend(saved.0);
+        }
+
+        map
+    }
 
-use crate::game_state::GameState;
+    pub fn save(&self) {
+        storage::save(BINDINGS_KEY, self);
+    }
 
-const INPUT_UP: u8 = 1 << 0;
-const INPUT_DOWN: u8 = 1 << 1;
-const INPUT_LEFT: u8 = 1 << 2;
-const INPUT_RIGHT: u8 = 1 << 3;
-const INPUT_DASH: u8 = 1 << 4;
+    pub fn bindings(&self, action: Action) -> &[Binding] {
+        self.0.get(&action).map_or(&[], Vec::as_slice)
+    }
 
-#[derive(Resource, Default, Debug, Deref)]
-pub struct Input(u8);
+    /// Replaces the bindings of `action` on `binding`'s device, keeping those
+    /// on the other devices.
+    pub fn rebind(&mut self, action: Action, binding: Binding) {
+        let bindings = self.0.entry(action).or_default();
+        bindings.retain(|bound| mem::discriminant(bound) != mem::discriminant(&binding));
+        bindings.push(binding);
+    }
+}
 
-#[derive(Resource, Default, Debug, Deref)]
-pub struct MousePos(Vec2);
+/// Actions held this frame. Gameplay reads this, never raw devices.
+#[derive(Resource, Default, Debug, Clone, Copy, PartialEq)]
+pub struct ActionState {
+    /// One bit per [`Action`].
+    pub buttons: u8,
+    /// Movement with analog magnitude, at most unit length.
+    pub movement: Vec2,
+}
+
+/// Where the dash is aimed, following whichever device was used last.
+#[derive(Resource, Debug, Clone, Copy, PartialEq)]
+pub enum Aim {
+    /// World position of the mouse cursor.
+    Cursor(Vec2),
+    /// Stick direction, or the movement direction when no stick is deflected.
+    Direction(Vec2),
+}
+
+impl Default for Aim {
+    fn default() -> Self {
+        Self::Direction(Vec2::X)
+    }
+}
+
+impl Aim {
+    pub fn dir_from(&self, pos: Vec2) -> Vec2 {
+        match *self {
+            Aim::Cursor(target) => (target - pos).normalize_or_zero(),
+            Aim::Direction(dir) => dir,
+        }
+    }
+}
+
+/// Whether the mouse is on UI that takes clicks while playing, such as the
+/// tuning panel. Mouse bindings are ignored meanwhile.
+#[derive(Resource, Default, Debug, Clone, Copy, PartialEq)]
+pub struct PointerOverUi(pub bool);
 
 pub struct InputPlugin;
 
 impl Plugin for InputPlugin {
     fn build(&self, app: &mut App) {
-        app.init_resource::<Input>()
-            .init_resource::<MousePos>()
+        app.insert_resource(ActionMap::load())
+            .init_resource::<ActionState>()
+            .init_resource::<Aim>()
+            .init_resource::<PointerOverUi>()
             .add_systems(
                 Update,
-                (read_inputs, read_mouse_position).run_if(in_state(GameState::Running)),
+                (read_actions, read_aim)
+                    .chain()
+                    .run_if(in_state(GameState::Running).and(not(is_playing_back))),
             );
     }
 }
 
-fn read_inputs(
+fn read_actions(
     mut commands: Commands,
     keys: Res<ButtonInput<KeyCode>>,
     mouse: Res<ButtonInput<MouseButton>>,
+    gamepads: Query<&Gamepad>,
+    touch: Res<TouchControls>,
+    map: Res<ActionMap>,
+    pointer_over_ui: Res<PointerOverUi>,
 ) {
-    let mut input = 0u8;
+    let mut state = ActionState::default();
 
-    if keys.any_pressed([KeyCode::KeyW]) {
-        input |= INPUT_UP;
-    }
-    if keys.any_pressed([KeyCode::KeyS]) {
-        input |= INPUT_DOWN;
-    }
-    if keys.any_pressed([KeyCode::KeyA]) {
-        input |= INPUT_LEFT
-    }
-    if keys.any_pressed([KeyCode::KeyD]) {
-        input |= INPUT_RIGHT;
+    for action in Action::ALL {
+        if map
+            .bindings(action)
+            .iter()
+            .filter(|binding| !(pointer_over_ui.0 && matches!(binding, Binding::Mouse(_))))
+            .any(|binding| binding.pressed(&keys, &mouse, &gamepads))
+        {
+            state.buttons |= action.bit();
+        }
     }
-    if mouse.any_pressed([MouseButton::Left]) {
-        input |= INPUT_DASH;
+
+    if touch.dash_held() {
+        state.buttons |= Action::Dash.bit();
     }
 
-    commands.insert_resource(Input(input));
+    let stick = gamepads
+        .iter()
+        .map(Gamepad::left_stick)
+        .find(|stick| stick.length() > STICK_DEADZONE);
+
+    state.movement = match stick {
+        Some(stick) => stick.clamp_length_max(1.),
+        None if touch.joystick.is_some() => touch.movement(),
+        None => state.digital_dir(),
+    };
+
+    commands.insert_resource(state);
 }
 
-fn read_mouse_position(
-    mut commands: Commands,
+fn read_aim(
+    mut aim: ResMut<Aim>,
+    mut cursor_moved: EventReader<CursorMoved>,
+    actions: Res<ActionState>,
+    touch: Res<TouchControls>,
+    gamepads: Query<&Gamepad>,
     windows: Query<&Window>,
     camera_q: Query<(&Camera, &GlobalTransform), With<Camera2d>>,
 ) {
-    let window = windows.single().unwrap();
-    let (camera, camera_transform) = camera_q.single().unwrap();
+    let stick = gamepads.iter().find_map(|gamepad| {
+        [gamepad.right_stick(), gamepad.left_stick()]
+            .into_iter()
+            .find(|stick| stick.length() > STICK_DEADZONE)
+    });
+
+    let cursor_moved = cursor_moved.read().count() > 0;
 
-    if let Some(Ok(world_position)) = window
-        .cursor_position()
-        .map(|cursor| camera.viewport_to_world_2d(camera_transform, cursor))
-    {
-        commands.insert_resource(MousePos(world_position));
+    if let Some(dir) = touch.aim_dir() {
+        *aim = Aim::Direction(dir);
+    } else if let Some(stick) = stick {
+        *aim = Aim::Direction(stick.normalize());
+    } else if cursor_moved || matches!(*aim, Aim::Cursor(_)) {
+        let window = windows.single().unwrap();
+        let (camera, camera_transform) = camera_q.single().unwrap();
+
+        // the camera moves, so the cursor's world position is refreshed every frame
+        if let Some(Ok(world_position)) = window
+            .cursor_position()
+            .map(|cursor| camera.viewport_to_world_2d(camera_transform, cursor))
+        {
+            *aim = Aim::Cursor(world_position);
+        }
+    } else if actions.dir() != Vec2::ZERO {
+        *aim = Aim::Direction(actions.dir().normalize());
     }
 }
 
-impl Input {
+impl ActionState {
+    pub fn pressed(&self, action: Action) -> bool {
+        self.buttons & action.bit() != 0
+    }
+
     pub fn dir(&self) -> Vec2 {
+        self.movement
+    }
+
+    fn digital_dir(&self) -> Vec2 {
         let mut dir = Vec2::ZERO;
 
-        if self.0 & INPUT_UP != 0 {
+        if self.pressed(Action::Up) {
             dir.y += 1.;
         }
-        if self.0 & INPUT_DOWN != 0 {
+        if self.pressed(Action::Down) {
             dir.y -= 1.;
         }
-        if self.0 & INPUT_RIGHT != 0 {
+        if self.pressed(Action::Right) {
             dir.x += 1.;
         }
-        if self.0 & INPUT_LEFT != 0 {
+        if self.pressed(Action::Left) {
             dir.x -= 1.;
         }
 
         dir.normalize_or_zero()
     }
-
-    pub fn dash(&self) -> bool {
-        self.0 & INPUT_DASH != 0
-    }
 }
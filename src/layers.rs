@@ -0,0 +1,100 @@
+use std::ops::BitOr;
+
+use bevy::prelude::*;
+
+/// Collision layer bitmask an entity belongs to.
+///
+/// Which layers affect each other, and how, is declared once in [`INTERACTIONS`].
+#[derive(Component, Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct CollisionLayers(u32);
+
+impl CollisionLayers {
+    pub const PLAYER: Self = Self(1 << 0);
+    pub const ENEMY: Self = Self(1 << 1);
+    pub const ENEMY_PROJECTILE: Self = Self(1 << 2);
+    pub const PLAYER_PROJECTILE: Self = Self(1 << 3);
+    pub const OBSTACLE: Self = Self(1 << 4);
+    pub const PICKUP: Self = Self(1 << 5);
+    pub const TRIGGER: Self = Self(1 << 6);
+    pub const HAZARD: Self = Self(1 << 7);
+
+    pub const fn union(self, other: Self) -> Self {
+        Self(self.0 | other.0)
+    }
+
+    pub const fn intersects(self, other: Self) -> bool {
+        self.0 & other.0 != 0
+    }
+
+    /// How a body on these layers, moving into `other`, is affected by it.
+    pub fn interaction(self, other: Self) -> Option<Interaction> {
+        INTERACTIONS
+            .iter()
+            .find(|(mover, target, _)| self.intersects(*mover) && other.intersects(*target))
+            .map(|(.., interaction)| *interaction)
+    }
+
+    /// Whether bodies on these layers need to look for contacts at all.
+    pub fn is_mover(self) -> bool {
+        INTERACTIONS
+            .iter()
+            .any(|(mover, ..)| self.intersects(*mover))
+    }
+}
+
+impl BitOr for CollisionLayers {
+    type Output = Self;
+
+    fn bitor(self, rhs: Self) -> Self {
+        self.union(rhs)
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Interaction {
+    /// Stops and hurts the mover; while it is immune it is only stopped.
+    Solid,
+    /// Stops and hurts the mover; passed through while it is immune.
+    Body,
+    /// Hurts the mover without stopping it; passed through while it is immune.
+    Hazard,
+    /// The mover damages whatever it overlaps.
+    Blast,
+    /// Noticed by the mover without any physical response.
+    Sensor,
+}
+
+impl Interaction {
+    pub fn blocks(self) -> bool {
+        matches!(self, Self::Solid | Self::Body)
+    }
+}
+
+/// `(mover, target, interaction)` rules, first match wins.
+pub const INTERACTIONS: &[(CollisionLayers, CollisionLayers, Interaction)] = &[
+    (
+        CollisionLayers::PLAYER,
+        CollisionLayers::OBSTACLE,
+        Interaction::Solid,
+    ),
+    (
+        CollisionLayers::PLAYER,
+        CollisionLayers::ENEMY,
+        Interaction::Body,
+    ),
+    (
+        CollisionLayers::PLAYER,
+        CollisionLayers::ENEMY_PROJECTILE.union(CollisionLayers::HAZARD),
+        Interaction::Hazard,
+    ),
+    (
+        CollisionLayers::PLAYER,
+        CollisionLayers::PICKUP.union(CollisionLayers::TRIGGER),
+        Interaction::Sensor,
+    ),
+    (
+        CollisionLayers::PLAYER_PROJECTILE,
+        CollisionLayers::ENEMY,
+        Interaction::Blast,
+    ),
+];
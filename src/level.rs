@@ -0,0 +1,336 @@
+use bevy::{prelude::*, sprite::Anchor};
+use rand::Rng;
+use serde::{ser::SerializeStruct, Deserialize, Serialize, Serializer};
+
+use crate::{
+    asset_loader::LevelAssets,
+    collider::Collider,
+    config::{Config, Validate},
+    game_state::GameState,
+    layers::CollisionLayers,
+};
+
+const POLE_WIDTH: f32 = 6.;
+const POWER_LINE_RADIUS: f32 = 2.;
+/// Wind particles per 10k square units of zone area.
+const WIND_PARTICLE_DENSITY: f32 = 1.5;
+/// Particle drift speed per unit of zone strength.
+const WIND_PARTICLE_SPEED: f32 = 0.4;
+
+#[derive(Asset, TypePath, Debug, Serialize, Deserialize, Clone)]
+pub struct LevelData {
+    pub obstacles: Vec<ObstacleData>,
+    #[serde(default)]
+    pub wind_zones: Vec<WindZoneData>,
+}
+
+impl Validate for LevelData {
+    fn validate(&self) -> Vec<String> {
+        self.wind_zones
+            .iter()
+            .enumerate()
+            .filter(|(_, zone)| zone.min.cmpge(zone.max).any())
+            .map(|(i, zone)| {
+                format!(
+                    "wind_zones[{i}]: min {} must be below max {}",
+                    zone.min, zone.max
+                )
+            })
+            .collect()
+    }
+}
+
+/// Obstacle placement in world units. Buildings and poles stand on the floor at
+/// `x`; power lines run between two points.
+#[derive(Debug, Deserialize, Clone)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum ObstacleData {
+    Building { x: f32, width: f32, height: f32 },
+    Pole { x: f32, height: f32 },
+    PowerLine { from: Vec2, to: Vec2 },
+}
+
+/// Written as the unit variant of this rather than the derived string tag, so
+/// RON writes an identifier it can read back.
+#[derive(Serialize)]
+#[serde(rename_all = "snake_case")]
+enum ObstacleKind {
+    Building,
+    Pole,
+    PowerLine,
+}
+
+impl Serialize for ObstacleData {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match self {
+            ObstacleData::Building { x, width, height } => {
+                let mut state = serializer.serialize_struct("ObstacleData", 4)?;
+                state.serialize_field("kind", &ObstacleKind::Building)?;
+                state.serialize_field("x", x)?;
+                state.serialize_field("width", width)?;
+                state.serialize_field("height", height)?;
+                state.end()
+            }
+            ObstacleData::Pole { x, height } => {
+                let mut state = serializer.serialize_struct("ObstacleData", 3)?;
+                state.serialize_field("kind", &ObstacleKind::Pole)?;
+                state.serialize_field("x", x)?;
+                state.serialize_field("height", height)?;
+                state.end()
+            }
+            ObstacleData::PowerLine { from, to } => {
+                let mut state = serializer.serialize_struct("ObstacleData", 3)?;
+                state.serialize_field("kind", &ObstacleKind::PowerLine)?;
+                state.serialize_field("from", from)?;
+                state.serialize_field("to", to)?;
+                state.end()
+            }
+        }
+    }
+}
+
+/// Rectangular force volume between `min` and `max`. `strength` is the
+/// acceleration along `direction`; `noise` scales the turbulence relative to it.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct WindZoneData {
+    pub min: Vec2,
+    pub max: Vec2,
+    pub direction: Vec2,
+    pub strength: f32,
+    #[serde(default)]
+    pub noise: f32,
+}
+
+#[derive(Component, Default, Debug)]
+#[require(CollisionLayers = CollisionLayers::OBSTACLE)]
+pub struct Obstacle;
+
+/// Live wire: hurts on touch like a drone's shot, without stopping the pigeon.
+#[derive(Component, Default, Debug)]
+#[require(CollisionLayers = CollisionLayers::HAZARD)]
+pub struct Electrified {
+    pub timer: Timer,
+}
+
+/// Accelerates anything moving inside it.
+#[derive(Component, Default, Debug)]
+pub struct WindZone {
+    pub half_size: Vec2,
+    pub direction: Vec2,
+    pub strength: f32,
+    pub noise: f32,
+}
+
+impl WindZone {
+    pub fn contains(&self, center: Vec2, point: Vec2) -> bool {
+        let d = (point - center).abs();
+        d.x <= self.half_size.x && d.y <= self.half_size.y
+    }
+
+    /// Acceleration at `point` after `elapsed` seconds.
+    pub fn force_at(&self, point: Vec2, elapsed: f32) -> Vec2 {
+        // cheap deterministic turbulence, varying across the zone and over time
+        let gust = Vec2::new(
+            (elapsed * 1.7 + point.y * 0.013).sin(),
+            (elapsed * 2.3 + point.x * 0.011).cos(),
+        );
+
+        (self.direction + gust * self.noise) * self.strength
+    }
+}
+
+#[derive(Component, Default, Debug)]
+struct WindParticle;
+
+pub struct LevelPlugin;
+
+impl Plugin for LevelPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            OnEnter(GameState::Running),
+            (spawn_obstacles, spawn_wind_zones),
+        )
+        .add_systems(
+            Update,
+            (electrified_system, wind_particle_system).run_if(in_state(GameState::Running)),
+        );
+    }
+}
+
+fn spawn_obstacles(
+    mut commands: Commands,
+    obstacles: Query<Entity, Or<(With<Obstacle>, With<Electrified>)>>,
+    level_assets: Res<LevelAssets>,
+    levels: Res<Assets<LevelData>>,
+    cfg: Res<Config>,
+) {
+    for obstacle in &obstacles {
+        commands.entity(obstacle).despawn();
+    }
+
+    let Some(level) = levels.get(level_assets.city.id()) else {
+        log::warn!("Level data is not loaded, skipping obstacles");
+        return;
+    };
+
+    for obstacle in &level.obstacles {
+        spawn_obstacle(&mut commands, obstacle, &cfg);
+    }
+}
+
+fn spawn_obstacle(commands: &mut Commands, obstacle: &ObstacleData, cfg: &Config) {
+    let floor_y = cfg.game.floor_y;
+
+    match *obstacle {
+        ObstacleData::Building { x, width, height } => {
+            let half_size = Vec2::new(width, height) / 2.;
+
+            // borrows the tint of the nearest parallax building layer, but stands
+            // in the world and scrolls with it rather than with the parallax
+            let sprite = Sprite {
+                color: Color::srgb_u8(69, 176, 209),
+                custom_size: Some(Vec2::new(width, height)),
+                ..default()
+            };
+
+            commands.spawn((
+                Obstacle,
+                Collider::aabb(half_size),
+                Transform::from_translation(Vec3::new(x, floor_y + half_size.y, -10.)),
+                sprite,
+            ));
+        }
+        ObstacleData::Pole { x, height } => {
+            let sprite = Sprite {
+                color: Color::srgb_u8(119, 150, 181),
+                custom_size: Some(Vec2::new(POLE_WIDTH, height)),
+                ..default()
+            };
+
+            commands.spawn((
+                Obstacle,
+                Collider::aabb(Vec2::new(POLE_WIDTH, height) / 2.),
+                Transform::from_translation(Vec3::new(x, floor_y + height / 2., -10.)),
+                sprite,
+            ));
+        }
+        ObstacleData::PowerLine { from, to } => {
+            let center = (from + to) / 2.;
+            let span = to - from;
+            let rotation = Vec2::Y.angle_to(span);
+
+            let sprite = Sprite {
+                color: Color::srgb_u8(200, 200, 10),
+                custom_size: Some(Vec2::new(POWER_LINE_RADIUS * 2., span.length())),
+                anchor: Anchor::Center,
+                ..default()
+            };
+
+            commands.spawn((
+                Electrified {
+                    timer: Timer::from_seconds(0.08, TimerMode::Repeating),
+                },
+                Collider::capsule(span.length() / 2., POWER_LINE_RADIUS, rotation),
+                Transform::from_translation(center.extend(-9.))
+                    .with_rotation(Quat::from_rotation_z(rotation)),
+                sprite,
+            ));
+        }
+    }
+}
+
+fn spawn_wind_zones(
+    mut commands: Commands,
+    zones: Query<Entity, With<WindZone>>,
+    level_assets: Res<LevelAssets>,
+    levels: Res<Assets<LevelData>>,
+) {
+    for zone in &zones {
+        commands.entity(zone).despawn();
+    }
+
+    let Some(level) = levels.get(level_assets.city.id()) else {
+        return;
+    };
+
+    let mut rng = rand::rng();
+
+    for zone in &level.wind_zones {
+        let center = (zone.min + zone.max) / 2.;
+        let half_size = (zone.max - zone.min).abs() / 2.;
+
+        let particle_count =
+            (half_size.x * half_size.y * 4. / 10_000. * WIND_PARTICLE_DENSITY).ceil() as usize;
+
+        commands
+            .spawn((
+                WindZone {
+                    half_size,
+                    direction: zone.direction.normalize_or_zero(),
+                    strength: zone.strength,
+                    noise: zone.noise,
+                },
+                Transform::from_translation(center.extend(-8.)),
+                Visibility::default(),
+            ))
+            .with_children(|parent| {
+                for _ in 0..particle_count {
+                    let offset = Vec2::new(
+                        rng.random_range(-half_size.x..=half_size.x),
+                        rng.random_range(-half_size.y..=half_size.y),
+                    );
+
+                    parent.spawn((
+                        WindParticle,
+                        Sprite {
+                            color: Color::srgba(1., 1., 1., 0.15),
+                            custom_size: Some(Vec2::new(2., 2.)),
+                            ..default()
+                        },
+                        Transform::from_translation(offset.extend(0.)),
+                    ));
+                }
+            });
+    }
+}
+
+/// Drifts particles with their zone's wind, wrapping them around its bounds.
+fn wind_particle_system(
+    zones: Query<(&WindZone, &Transform, &Children)>,
+    mut particles: Query<&mut Transform, (With<WindParticle>, Without<WindZone>)>,
+    time: Res<Time>,
+) {
+    let dt = time.delta_secs();
+    let elapsed = time.elapsed_secs();
+
+    for (zone, zone_transform, children) in zones.iter() {
+        let center = zone_transform.translation.truncate();
+        let size = zone.half_size * 2.;
+
+        for child in children.iter() {
+            let Ok(mut transform) = particles.get_mut(child) else {
+                continue;
+            };
+
+            let local = transform.translation.truncate();
+            let vel = zone.force_at(center + local, elapsed) * WIND_PARTICLE_SPEED;
+
+            let moved = local + vel * dt + zone.half_size;
+            let wrapped = moved.rem_euclid(size.max(Vec2::ONE)) - zone.half_size;
+
+            transform.translation.x = wrapped.x;
+            transform.translation.y = wrapped.y;
+        }
+    }
+}
+
+fn electrified_system(mut lines: Query<(&mut Electrified, &mut Sprite)>, time: Res<Time>) {
+    for (mut electrified, mut sprite) in lines.iter_mut() {
+        electrified.timer.tick(time.delta());
+
+        if electrified.timer.just_finished() {
+            let alpha = if sprite.color.alpha() < 1. { 1. } else { 0.5 };
+            sprite.color.set_alpha(alpha);
+        }
+    }
+}
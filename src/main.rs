@@ -7,19 +7,31 @@ use bevy::{
     log::{Level, LogPlugin},
     prelude::*,
 };
-use clap::Parser;
 
 mod animation;
 mod args;
 mod asset_loader;
+mod collider;
 mod config;
+#[cfg(not(target_arch = "wasm32"))]
+mod convert;
+mod difficulty;
 mod enemy;
 mod game_state;
 mod health;
+mod high_score;
 mod input;
+mod layers;
+mod level;
 mod physics;
 mod player;
+mod replay;
 mod score;
+mod sfx;
+mod spatial;
+mod stats;
+mod storage;
+mod touch;
 mod ui;
 mod world;
 
@@ -27,23 +39,38 @@ mod world;
 extern crate console_error_panic_hook;
 
 use crate::{
-    animation::AnimationPlugin, asset_loader::AssetLoaderPlugin, enemy::EnemyPlugin,
-    health::HealthPlugin, input::InputPlugin, physics::PhysicsPlugin, player::PlayerPlugin,
-    score::ScorePlugin, ui::UIPlugin, world::WorldPlugin,
+    animation::AnimationPlugin, asset_loader::AssetLoaderPlugin, difficulty::DifficultyPlugin,
+    enemy::EnemyPlugin, health::HealthPlugin, high_score::HighScorePlugin, input::InputPlugin,
+    level::LevelPlugin, physics::PhysicsPlugin, player::PlayerPlugin, replay::ReplayPlugin,
+    score::ScorePlugin, sfx::SfxPlugin, stats::StatsPlugin, touch::TouchPlugin, ui::UIPlugin,
+    world::WorldPlugin,
 };
 
 fn main() {
     #[cfg(target_arch = "wasm32")]
     console_error_panic_hook::set_once();
 
-    let args = Args::parse();
+    let args = Args::from_env();
 
-    App::new()
-        .insert_resource(args)
+    #[cfg(not(target_arch = "wasm32"))]
+    if let Some([input, output]) = args.convert.as_deref() {
+        if let Err(err) = convert::convert(input, output) {
+            eprintln!("{err}");
+            std::process::exit(1);
+        }
+        return;
+    }
+
+    let mut app = App::new();
+
+    #[cfg(not(target_arch = "wasm32"))]
+    asset_loader::register_config_source(&mut app, &args);
+
+    app.insert_resource(args)
         .add_plugins(
             DefaultPlugins
                 .set(LogPlugin {
-                    filter: "warn,ui=info".to_string(),
+                    filter: "warn,ui=info,hacker_pigeon=info".to_string(),
                     level: Level::INFO,
                     ..Default::default()
                 })
@@ -68,15 +95,63 @@ fn main() {
         )
         .add_plugins((
             AssetLoaderPlugin,
+            DifficultyPlugin,
             WorldPlugin,
+            LevelPlugin,
             InputPlugin,
+            ReplayPlugin,
+            TouchPlugin,
             PlayerPlugin,
             EnemyPlugin,
             PhysicsPlugin,
             HealthPlugin,
-            ScorePlugin,
+            (ScorePlugin, HighScorePlugin, StatsPlugin),
+            SfxPlugin,
             AnimationPlugin,
             UIPlugin,
         ))
         .run();
 }
+
+#[cfg(test)]
+mod tests {
+    use bevy::{
+        ecs::schedule::{LogLevel, ScheduleBuildSettings},
+        state::app::StatesPlugin,
+    };
+
+    use super::*;
+    use crate::game_state::GameState;
+
+    /// Systems touching the same data must run in a fixed order, or replays
+    /// drift apart once the executor picks a different one.
+    #[test]
+    fn simulation_has_no_ambiguous_systems() {
+        let mut app = App::new();
+        app.add_plugins((MinimalPlugins, StatesPlugin))
+            .init_state::<GameState>()
+            .add_plugins((
+                PhysicsPlugin,
+                PlayerPlugin,
+                EnemyPlugin,
+                HealthPlugin,
+                ScorePlugin,
+                SfxPlugin,
+                StatsPlugin,
+            ));
+
+        let mut schedule = app
+            .world_mut()
+            .resource_mut::<Schedules>()
+            .remove(FixedUpdate)
+            .unwrap();
+        schedule.set_build_settings(ScheduleBuildSettings {
+            ambiguity_detection: LogLevel::Error,
+            ..default()
+        });
+
+        if let Err(err) = schedule.initialize(app.world_mut()) {
+            panic!("{err}");
+        }
+    }
+}
@@ -2,36 +2,120 @@ use bevy::prelude::*;
 use std::time::Duration;
 
 use crate::{
+    collider::Collider,
     config::Config,
-    enemy::{Enemy, WeakSpot},
+    enemy::{Enemy, EnemyProjectile, WeakSpot},
     game_state::GameState,
-    health::Health,
+    health::{Health, Killed},
+    layers::{CollisionLayers, Interaction},
+    level::WindZone,
     player::{ChargingDash, DashEffect, Dashing, Nuke, Player},
-    score::Score,
+    replay::RunClock,
+    spatial::SpatialGrid,
 };
 
-#[derive(Component, Default, Debug, Deref, DerefMut)]
-pub struct Radius(pub f32);
-
 #[derive(Component, Default, Debug)]
+#[require(Interpolated, Mass, PhysicsBody)]
 pub struct Velocity {
     pub current: Vec2,
     pub target: Vec2,
 }
 
+impl Velocity {
+    /// Applies an instantaneous `impulse` to a body of `mass`.
+    pub fn apply_impulse(&mut self, impulse: Vec2, mass: f32) {
+        let delta = impulse / mass.max(f32::EPSILON);
+        self.current += delta;
+        self.target += delta;
+    }
+
+    /// Reflects the part of the velocity heading into a surface with `normal`,
+    /// keeping `restitution` of it.
+    pub fn bounce(&mut self, normal: Vec2, restitution: f32) {
+        for vel in [&mut self.current, &mut self.target] {
+            let into = vel.dot(normal);
+            if into < 0. {
+                *vel -= (1. + restitution) * into * normal;
+            }
+        }
+    }
+}
+
+#[derive(Component, Debug, Deref, DerefMut)]
+pub struct Mass(pub f32);
+
+impl Default for Mass {
+    fn default() -> Self {
+        Self(1.)
+    }
+}
+
+/// Per-entity movement tuning. Unset values fall back to the `GameConfig` defaults.
+#[derive(Component, Clone, Copy, Debug)]
+pub struct PhysicsBody {
+    pub gravity_scale: f32,
+    pub air_friction: Option<f32>,
+    pub ground_friction: Option<f32>,
+    pub max_speed: Option<f32>,
+}
+
+impl Default for PhysicsBody {
+    fn default() -> Self {
+        Self {
+            gravity_scale: 1.,
+            air_friction: None,
+            ground_friction: None,
+            max_speed: None,
+        }
+    }
+}
+
+/// Suppresses input and steering until the timer runs out.
+#[derive(Component, Default, Debug)]
+pub struct Stunned {
+    pub timer: Timer,
+}
+
+impl Stunned {
+    pub fn new(duration_secs: f32) -> Self {
+        Self {
+            timer: Timer::from_seconds(duration_secs, TimerMode::Once),
+        }
+    }
+}
+
+/// Smooths the rendered `Transform` between fixed simulation ticks.
+///
+/// During the fixed loop the `Transform` holds the simulated translation; outside
+/// of it the translation is interpolated between the last two ticks.
+#[derive(Component, Default, Debug)]
+pub struct Interpolated {
+    previous: Vec3,
+    current: Vec3,
+}
+
+impl Interpolated {
+    /// Translation at the start of the current fixed tick.
+    pub fn previous(&self) -> Vec3 {
+        self.previous
+    }
+}
+
 #[derive(Component, Default, Debug)]
 pub struct Grounded;
 
+/// Left alone by wind, for bodies with nothing to pull them back into place,
+/// such as ground turrets.
 #[derive(Component, Default, Debug)]
-pub struct Airborne;
+pub struct Anchored;
 
 #[derive(Component, Default, Debug)]
-struct CollisionImmunity {
+pub struct CollisionImmunity {
     timer: Timer,
 }
 
 impl CollisionImmunity {
-    fn new(duration_secs: f32) -> Self {
+    pub fn new(duration_secs: f32) -> Self {
         Self {
             timer: Timer::from_seconds(duration_secs, TimerMode::Once),
         }
@@ -51,62 +135,243 @@ impl Blink {
     }
 }
 
+/// The player touched something that hurts.
+#[derive(Event, Debug)]
+pub struct PlayerHit {
+    pub player: Entity,
+    pub source: Entity,
+    /// Player position at the moment of contact.
+    pub contact: Vec2,
+    /// Direction pushing the player away from the source.
+    pub normal: Vec2,
+    /// Whether the source stops the player rather than being passed through.
+    pub solid: bool,
+}
+
+/// An immune player ran into something solid, which stops it without hurting it.
+#[derive(Event, Debug)]
+pub struct PlayerBlocked {
+    pub player: Entity,
+    /// Player position at the moment of contact.
+    pub contact: Vec2,
+    /// Direction pushing the player away from the obstacle.
+    pub normal: Vec2,
+}
+
+/// The player reached an enemy's weak spot.
+#[derive(Event, Debug)]
+pub struct EnemyWeakSpotHit {
+    pub player: Entity,
+    pub enemy: Entity,
+    /// Player position at the moment of contact.
+    pub contact: Vec2,
+    /// Direction pushing the player away from the weak spot.
+    pub normal: Vec2,
+}
+
+/// A nuke blast reached an enemy.
+#[derive(Event, Debug)]
+pub struct NukeHit {
+    pub nuke: Entity,
+    pub enemy: Entity,
+}
+
+/// Gameplay moving bodies on its own, before physics integrates and collides
+/// them.
+#[derive(SystemSet, Debug, Clone, PartialEq, Eq, Hash)]
+pub enum MovementSystems {
+    Player,
+    Enemies,
+}
+
+#[derive(SystemSet, Debug, Clone, PartialEq, Eq, Hash)]
+pub enum CollisionSystems {
+    /// Contact detection, emitting collision events.
+    Detect,
+    /// Damage and death resolution from collision events.
+    Resolve,
+    /// Everything reacting to the outcome: score, audio, effects, state changes.
+    React,
+}
+
 pub struct PhysicsPlugin;
 
 impl Plugin for PhysicsPlugin {
     fn build(&self, app: &mut App) {
-        app.add_systems(
-            Update,
-            (
-                gravity_system,
-                friction_system,
-                apply_velocity_system,
-                apply_grounding_system,
-                player_damage_drone_system,
-                player_drone_collision_system,
-                nuke_drone_collision_system,
-                collision_immunity_system,
-                blink_system,
+        app.init_resource::<SpatialGrid>()
+            .add_systems(
+                RunFixedMainLoop,
+                (
+                    restore_interpolated_system.in_set(RunFixedMainLoopSystem::BeforeFixedMainLoop),
+                    interpolate_transform_system.in_set(RunFixedMainLoopSystem::AfterFixedMainLoop),
+                ),
             )
-                .chain()
-                .run_if(in_state(GameState::Running)),
-        );
+            .add_systems(FixedFirst, snapshot_previous_system)
+            .add_systems(FixedLast, snapshot_current_system)
+            .add_event::<PlayerHit>()
+            .add_event::<PlayerBlocked>()
+            .add_event::<EnemyWeakSpotHit>()
+            .add_event::<NukeHit>()
+            .configure_sets(
+                FixedUpdate,
+                (MovementSystems::Player, MovementSystems::Enemies)
+                    .chain()
+                    .before(CollisionSystems::Detect),
+            )
+            .configure_sets(
+                FixedUpdate,
+                (
+                    CollisionSystems::Detect,
+                    CollisionSystems::Resolve,
+                    CollisionSystems::React,
+                )
+                    .chain()
+                    .run_if(in_state(GameState::Running)),
+            )
+            .add_systems(
+                FixedUpdate,
+                (
+                    gravity_system,
+                    wind_system,
+                    friction_system,
+                    apply_velocity_system,
+                    apply_grounding_system,
+                    rebuild_spatial_grid_system,
+                )
+                    .chain()
+                    .after(MovementSystems::Enemies)
+                    .before(CollisionSystems::Detect)
+                    .run_if(in_state(GameState::Running)),
+            )
+            .add_systems(
+                FixedUpdate,
+                (weak_spot_collision_system, contact_system)
+                    .chain()
+                    .in_set(CollisionSystems::Detect),
+            )
+            .add_systems(
+                FixedUpdate,
+                (
+                    player_blocked_system,
+                    player_hit_knockback_system,
+                    weak_spot_rebound_system,
+                    nuke_knockback_system,
+                    player_hit_blink_system,
+                )
+                    .chain()
+                    .in_set(CollisionSystems::React),
+            )
+            .add_systems(
+                FixedUpdate,
+                (collision_immunity_system, stun_system, blink_system)
+                    .chain()
+                    .after(CollisionSystems::React)
+                    .run_if(in_state(GameState::Running)),
+            );
+    }
+}
+
+fn restore_interpolated_system(mut query: Query<(&mut Transform, &mut Interpolated)>) {
+    for (mut transform, mut interpolated) in query.iter_mut() {
+        if interpolated.is_added() {
+            interpolated.previous = transform.translation;
+            interpolated.current = transform.translation;
+        } else {
+            transform.translation = interpolated.current;
+        }
+    }
+}
+
+fn snapshot_previous_system(mut query: Query<(&Transform, &mut Interpolated)>) {
+    for (transform, mut interpolated) in query.iter_mut() {
+        interpolated.previous = transform.translation;
+    }
+}
+
+fn snapshot_current_system(mut query: Query<(&Transform, &mut Interpolated)>) {
+    for (transform, mut interpolated) in query.iter_mut() {
+        if interpolated.is_added() {
+            interpolated.previous = transform.translation;
+        }
+
+        interpolated.current = transform.translation;
+    }
+}
+
+fn interpolate_transform_system(
+    mut query: Query<(&mut Transform, &Interpolated)>,
+    time: Res<Time<Fixed>>,
+) {
+    let alpha = time.overstep_fraction();
+
+    for (mut transform, interpolated) in query.iter_mut() {
+        transform.translation = interpolated.previous.lerp(interpolated.current, alpha);
     }
 }
 
 fn gravity_system(
     mut query: Query<
-        (&mut Velocity, Option<&ChargingDash>),
-        (Without<Grounded>, Without<Dashing>, Without<Airborne>),
+        (&mut Velocity, &PhysicsBody, Option<&ChargingDash>),
+        (Without<Grounded>, Without<Dashing>),
     >,
     time: Res<Time>,
     cfg: Res<Config>,
 ) {
     let dt = time.delta_secs();
 
-    for (mut vel, charging_opt) in query.iter_mut() {
+    for (mut vel, body, charging_opt) in query.iter_mut() {
         let multiplier = if charging_opt.is_some() {
             cfg.game.charging_gravity_multiplier
         } else {
             1.0
         };
 
-        vel.target.y += cfg.game.gravity * multiplier * dt;
+        vel.target.y += cfg.game.gravity * body.gravity_scale * multiplier * dt;
+    }
+}
+
+fn wind_system(
+    zones: Query<(&WindZone, &Transform)>,
+    mut bodies: Query<
+        (&Transform, &mut Velocity),
+        (Without<Dashing>, Without<Anchored>, Without<WindZone>),
+    >,
+    mut projectiles: Query<(&Transform, &mut EnemyProjectile), Without<WindZone>>,
+    clock: Res<RunClock>,
+    time: Res<Time>,
+) {
+    let dt = time.delta_secs();
+    let elapsed = clock.elapsed;
+
+    let force_at = |point: Vec2| -> Vec2 {
+        zones
+            .iter()
+            .filter(|(zone, transform)| zone.contains(transform.translation.truncate(), point))
+            .map(|(zone, _)| zone.force_at(point, elapsed))
+            .sum()
+    };
+
+    for (transform, mut vel) in bodies.iter_mut() {
+        vel.target += force_at(transform.translation.truncate()) * dt;
+    }
+
+    for (transform, mut projectile) in projectiles.iter_mut() {
+        projectile.vel += force_at(transform.translation.truncate()) * dt;
     }
 }
 
 fn friction_system(
-    mut query: Query<(&mut Velocity, Option<&Grounded>)>,
+    mut query: Query<(&mut Velocity, &PhysicsBody, Option<&Grounded>)>,
     time: Res<Time>,
     cfg: Res<Config>,
 ) {
     let dt = time.delta_secs();
 
-    for (mut vel, grounded_opt) in query.iter_mut() {
+    for (mut vel, body, grounded_opt) in query.iter_mut() {
         let friction = if grounded_opt.is_some() {
-            cfg.game.ground_friction
+            body.ground_friction.unwrap_or(cfg.game.ground_friction)
         } else {
-            cfg.game.air_friction
+            body.air_friction.unwrap_or(cfg.game.air_friction)
         };
 
         vel.target.x *= (1.0 - friction * dt).max(0.0);
@@ -115,16 +380,20 @@ fn friction_system(
 }
 
 fn apply_velocity_system(
-    mut query: Query<(&mut Transform, &mut Velocity)>,
+    mut query: Query<(&mut Transform, &mut Velocity, &PhysicsBody)>,
     time: Res<Time>,
     cfg: Res<Config>,
 ) {
     let dt = time.delta_secs();
 
-    for (mut transform, mut vel) in query.iter_mut() {
+    for (mut transform, mut vel, body) in query.iter_mut() {
+        let max_speed = body.max_speed.unwrap_or(cfg.game.max_speed);
+
+        vel.target = vel.target.clamp_length_max(max_speed);
         vel.current = vel
             .current
-            .lerp(vel.target, (cfg.game.movement_smoothing * dt).min(1.0));
+            .lerp(vel.target, (cfg.game.movement_smoothing * dt).min(1.0))
+            .clamp_length_max(max_speed);
 
         transform.translation.x += vel.current.x * dt;
         transform.translation.y += vel.current.y * dt;
@@ -153,140 +422,399 @@ fn apply_grounding_system(
     }
 }
 
-fn player_drone_collision_system(
-    mut commands: Commands,
-    mut player: Query<
-        (Entity, &Transform, &Radius, &mut Velocity, &mut Health),
-        (With<Player>, Without<CollisionImmunity>),
+fn rebuild_spatial_grid_system(
+    mut grid: ResMut<SpatialGrid>,
+    colliders: Query<(
+        Entity,
+        &Transform,
+        &Collider,
+        Option<&Interpolated>,
+        Option<&WeakSpot>,
+    )>,
+) {
+    grid.clear();
+
+    for (entity, transform, collider, interpolated, weak_spot) in colliders.iter() {
+        let end = transform.translation.truncate();
+        let start = interpolated.map_or(end, |i| i.previous().truncate());
+
+        let body_reach = collider.bounding_radius();
+
+        // weak spots sit on the body edge and poke out past it
+        let reach = weak_spot.map_or(body_reach, |spot| {
+            let (spot_collider, offset) = spot.collider(body_reach);
+            body_reach.max(offset.length() + spot_collider.bounding_radius())
+        });
+
+        let (min, max) = swept_bounds(start, end, reach);
+        grid.insert(entity, min, max);
+    }
+}
+
+/// Axis-aligned bounds of a circle of `radius` moving from `start` to `end`.
+///
+/// Moving bodies are swept as circles of their collider's bounding radius.
+fn swept_bounds(start: Vec2, end: Vec2, radius: f32) -> (Vec2, Vec2) {
+    (start.min(end) - radius, start.max(end) + radius)
+}
+
+fn weak_spot_collision_system(
+    mut weak_spot_hits: EventWriter<EnemyWeakSpotHit>,
+    movers: Query<
+        (
+            Entity,
+            &Transform,
+            &Interpolated,
+            &Collider,
+            &CollisionLayers,
+            &DashEffect,
+        ),
+        Without<CollisionImmunity>,
+    >,
+    targets: Query<
+        (
+            &Transform,
+            &Interpolated,
+            &Collider,
+            &CollisionLayers,
+            &WeakSpot,
+        ),
+        Without<Killed>,
     >,
-    enemies: Query<(&Transform, &Radius), With<Enemy>>,
+    grid: Res<SpatialGrid>,
 ) {
-    if let Ok((player, player_transform, radius, mut vel, mut health)) = player.single_mut() {
-        let player_pos = player_transform.translation;
-        let player_size = **radius;
-
-        for (enemy_transform, radius) in enemies.iter() {
-            let enemy_pos = enemy_transform.translation;
-            let enemy_size = **radius;
-
-            let threshold = (player_size + enemy_size) * (player_size + enemy_size);
-
-            let dist_sq = (player_pos - enemy_pos).length_squared();
-            if dist_sq <= threshold {
-                if health.current > 0 {
-                    health.current -= 1;
-
-                    if health.current == 0 {
-                        commands.set_state(GameState::GameOver);
-                    } else {
-                        commands.entity(player).insert(CollisionImmunity::new(1.0));
-                        commands.entity(player).insert(Blink::new(50));
+    for (mover, transform, interpolated, collider, layers, dash) in movers.iter() {
+        let mover_start = interpolated.previous().truncate();
+        let mover_end = transform.translation.truncate();
+        let mover_radius = collider.bounding_radius();
+
+        let (min, max) = swept_bounds(mover_start, mover_end, mover_radius);
+
+        let mut hits: Vec<(f32, Entity, Vec2)> = grid
+            .query(min, max)
+            .into_iter()
+            .filter(|target| *target != mover)
+            .filter_map(|target| targets.get(target).ok().map(|query| (target, query)))
+            .filter(|(_, (.., target_layers, _))| layers.interaction(**target_layers).is_some())
+            .filter_map(
+                |(
+                    target,
+                    (target_transform, target_interpolated, target_collider, _, weak_spot),
+                )| {
+                    let target_pos = target_transform.translation.truncate();
+                    let target_start = target_interpolated.previous().truncate();
+                    let start = relative_start(mover_start, target_start, target_pos);
+
+                    let (spot_collider, spot_offset) =
+                        weak_spot.collider(target_collider.bounding_radius());
+
+                    let spot_t = spot_collider.sweep_circle(
+                        target_pos + spot_offset,
+                        start,
+                        mover_end,
+                        mover_radius,
+                    )?;
+
+                    // the body shields the weak spot when it is reached first
+                    let body_t =
+                        target_collider.sweep_circle(target_pos, start, mover_end, mover_radius);
+                    if body_t.is_some_and(|body_t| body_t < spot_t) {
+                        return None;
                     }
-                }
 
-                let dx = (enemy_pos.x - player_pos.x).abs();
-                let dy = (enemy_pos.y - player_pos.y).abs();
+                    let normal = spot_collider
+                        .normal_at(target_pos + spot_offset, start.lerp(mover_end, spot_t));
 
-                if dx > dy {
-                    vel.target.x *= -1.;
-                    vel.current.x *= -1.;
-                } else {
-                    vel.target.y *= -1.;
-                    vel.current.y *= -1.;
-                }
+                    Some((spot_t, target, normal))
+                },
+            )
+            .collect();
+
+        hits.sort_by(|(a, ..), (b, ..)| a.total_cmp(b));
 
+        for (t, enemy, normal) in hits {
+            weak_spot_hits.write(EnemyWeakSpotHit {
+                player: mover,
+                enemy,
+                contact: mover_start.lerp(mover_end, t),
+                normal,
+            });
+
+            if dash.power < 1.0 {
                 break;
             }
         }
     }
 }
 
-fn player_damage_drone_system(
-    mut commands: Commands,
-    mut score: ResMut<Score>,
-    mut player: Query<
-        (Entity, &Transform, &Radius, &mut Velocity, &mut DashEffect),
-        (With<Player>, Without<CollisionImmunity>),
+/// Finds contacts between every mover and the bodies its layers interact with.
+fn contact_system(
+    mut weak_spot_hits: EventReader<EnemyWeakSpotHit>,
+    mut player_hits: EventWriter<PlayerHit>,
+    mut player_blocked: EventWriter<PlayerBlocked>,
+    mut nuke_hits: EventWriter<NukeHit>,
+    movers: Query<
+        (
+            Entity,
+            &Transform,
+            &Collider,
+            &CollisionLayers,
+            Option<&Interpolated>,
+            Option<&DashEffect>,
+            Has<CollisionImmunity>,
+        ),
+        Without<Killed>,
+    >,
+    bodies: Query<
+        (
+            &Transform,
+            &Collider,
+            &CollisionLayers,
+            Option<&Interpolated>,
+        ),
+        Without<Killed>,
     >,
-    enemies: Query<(Entity, &Transform, &Radius, &WeakSpot), With<Enemy>>,
+    grid: Res<SpatialGrid>,
 ) {
-    if let Ok((player, player_transform, radius, mut vel, mut dash)) = player.single_mut() {
-        let player_pos = player_transform.translation.truncate();
-        let player_radius = **radius;
-
-        for (enemy, enemy_transform, radius, weak_spot) in enemies.iter() {
-            let enemy_pos = enemy_transform.translation.truncate();
-            let enemy_size = **radius;
-
-            let spot_offset = weak_spot.location.to_dir() * enemy_size;
-            let spot_center = enemy_pos + spot_offset;
-
-            let spot_half_size = weak_spot.size / 2.0;
-            let rotation = weak_spot.rotation;
-
-            let rel_pos = player_pos - spot_center;
-            let unrotated = rotation
-                .conjugate()
-                .mul_vec3(rel_pos.extend(0.0))
-                .truncate();
-
-            let dx = unrotated.x.clamp(-spot_half_size.x, spot_half_size.x) - unrotated.x;
-            let dy = unrotated.y.clamp(-spot_half_size.y, spot_half_size.y) - unrotated.y;
-            let dist_sq = dx * dx + dy * dy;
-
-            if dist_sq <= player_radius * player_radius {
-                commands.entity(enemy).despawn();
-
-                score.0 += 1 + dash.combo;
-                dash.combo += 1;
-
-                if dash.power < 1.0 {
-                    let dx = (enemy_pos.x - player_pos.x).abs();
-                    let dy = (enemy_pos.y - player_pos.y).abs();
-
-                    if dx > dy {
-                        vel.target.x *= -0.6;
-                        vel.current.x *= -0.6;
-                    } else {
-                        vel.target.y *= -0.6;
-                        vel.current.y *= -0.6;
-                    }
+    let weak_spot_hits: Vec<_> = weak_spot_hits.read().collect();
+    let mut blasted = Vec::new();
+
+    for (mover, transform, collider, layers, interpolated, dash, immune) in movers.iter() {
+        if !layers.is_mover() {
+            continue;
+        }
 
-                    commands.entity(player).remove::<Dashing>();
+        let mover_end = transform.translation.truncate();
+        let mover_start = interpolated.map_or(mover_end, |i| i.previous().truncate());
+        let mut mover_end = mover_end;
+        let mover_radius = collider.bounding_radius();
+
+        // a weak dash stops at the first weak spot it reaches
+        if dash.is_some_and(|dash| dash.power < 1.0) {
+            if let Some(hit) = weak_spot_hits.iter().find(|hit| hit.player == mover) {
+                mover_end = hit.contact;
+            }
+        }
+
+        let (min, max) = swept_bounds(mover_start, mover_end, mover_radius);
+
+        let mut hits = Vec::new();
+
+        for body in grid.query(min, max) {
+            if body == mover
+                || weak_spot_hits
+                    .iter()
+                    .any(|hit| hit.player == mover && hit.enemy == body)
+            {
+                continue;
+            }
+
+            let Ok((body_transform, body_collider, body_layers, body_interpolated)) =
+                bodies.get(body)
+            else {
+                continue;
+            };
+
+            let Some(interaction) = layers.interaction(*body_layers) else {
+                continue;
+            };
+
+            let body_pos = body_transform.translation.truncate();
+
+            match interaction {
+                Interaction::Blast => {
+                    if !blasted.contains(&body)
+                        && collider.intersects(mover_end, body_collider, body_pos)
+                    {
+                        nuke_hits.write(NukeHit {
+                            nuke: mover,
+                            enemy: body,
+                        });
+                        blasted.push(body);
+                    }
+                    continue;
                 }
+                // nothing reacts to sensors yet
+                Interaction::Sensor => continue,
+                Interaction::Body | Interaction::Hazard if immune => continue,
+                _ => {}
+            }
+
+            let start = body_interpolated.map_or(mover_start, |i| {
+                relative_start(mover_start, i.previous().truncate(), body_pos)
+            });
+
+            if let Some(t) = body_collider.sweep_circle(body_pos, start, mover_end, mover_radius) {
+                let normal = body_collider.normal_at(body_pos, start.lerp(mover_end, t));
+                hits.push((t, body, normal, interaction));
+            }
+        }
+
+        hits.sort_by(|(a, ..), (b, ..)| a.total_cmp(b));
+
+        // hazards passed on the way count too, but nothing past the first blocking body
+        for (t, body, normal, interaction) in hits {
+            let contact = mover_start.lerp(mover_end, t);
+
+            // only solids are left while immune, and they no longer hurt
+            if immune {
+                player_blocked.write(PlayerBlocked {
+                    player: mover,
+                    contact,
+                    normal,
+                });
+                break;
+            }
+
+            player_hits.write(PlayerHit {
+                player: mover,
+                source: body,
+                contact,
+                normal,
+                solid: interaction.blocks(),
+            });
+
+            if interaction.blocks() {
+                break;
             }
         }
     }
 }
 
-fn nuke_drone_collision_system(
+fn player_blocked_system(
+    mut player_blocked: EventReader<PlayerBlocked>,
+    mut players: Query<(&mut Transform, &mut Velocity), With<Player>>,
+    cfg: Res<Config>,
+) {
+    for blocked in player_blocked.read() {
+        let Ok((mut transform, mut vel)) = players.get_mut(blocked.player) else {
+            continue;
+        };
+
+        transform.translation.x = blocked.contact.x;
+        transform.translation.y = blocked.contact.y;
+        vel.bounce(blocked.normal, cfg.game.hit_restitution);
+    }
+}
+
+fn player_hit_knockback_system(
     mut commands: Commands,
-    mut score: ResMut<Score>,
-    mut nukes: Query<(&Transform, &Radius, &mut Nuke), (With<Nuke>, Without<Enemy>)>,
-    enemies: Query<(Entity, &Transform, &Radius), (With<Enemy>, Without<Nuke>)>,
+    mut player_hits: EventReader<PlayerHit>,
+    mut players: Query<(&mut Transform, &mut Velocity, &Mass), With<Player>>,
+    mut enemies: Query<(&mut Velocity, &Mass), (With<Enemy>, Without<Player>)>,
+    cfg: Res<Config>,
 ) {
-    for (enemy, enemy_transform, radius) in enemies.iter() {
-        let enemy_pos = enemy_transform.translation;
-        let enemy_size = **radius;
+    for hit in player_hits.read() {
+        if !hit.solid {
+            continue;
+        }
 
-        for (nuke_transform, radius, mut nuke) in nukes.iter_mut() {
-            let nuke_pos = nuke_transform.translation;
-            let nuke_size = **radius;
+        let Ok((mut transform, mut vel, mass)) = players.get_mut(hit.player) else {
+            continue;
+        };
 
-            let threshold = (nuke_size + enemy_size) * (nuke_size + enemy_size);
+        transform.translation.x = hit.contact.x;
+        transform.translation.y = hit.contact.y;
 
-            let dist_sq = (nuke_pos - enemy_pos).length_squared();
-            if dist_sq <= threshold {
-                commands.entity(enemy).despawn();
+        let impulse = hit.normal * cfg.game.hit_impulse;
 
-                score.0 += 1 + nuke.combo;
-                nuke.combo += 1;
-                break;
-            }
+        vel.bounce(hit.normal, cfg.game.hit_restitution);
+        vel.apply_impulse(impulse, **mass);
+
+        commands
+            .entity(hit.player)
+            .insert(Stunned::new(cfg.game.hit_stun_duration));
+
+        if let Ok((mut enemy_vel, enemy_mass)) = enemies.get_mut(hit.source) {
+            enemy_vel.apply_impulse(-impulse, **enemy_mass);
+            commands
+                .entity(hit.source)
+                .insert(Stunned::new(cfg.game.hit_stun_duration));
         }
     }
 }
 
+pub fn weak_spot_rebound_system(
+    mut commands: Commands,
+    mut weak_spot_hits: EventReader<EnemyWeakSpotHit>,
+    mut players: Query<(&mut Transform, &mut Velocity, &DashEffect), With<Player>>,
+    mut enemies: Query<(&mut Velocity, &Mass), (With<Enemy>, Without<Player>)>,
+    cfg: Res<Config>,
+) {
+    for hit in weak_spot_hits.read() {
+        let Ok((mut transform, mut vel, dash)) = players.get_mut(hit.player) else {
+            continue;
+        };
+
+        if let Ok((mut enemy_vel, enemy_mass)) = enemies.get_mut(hit.enemy) {
+            let impulse = -hit.normal * cfg.game.dash_knockback_impulse * dash.power;
+            enemy_vel.apply_impulse(impulse, **enemy_mass);
+        }
+
+        if dash.power >= 1.0 {
+            continue;
+        }
+
+        transform.translation.x = hit.contact.x;
+        transform.translation.y = hit.contact.y;
+
+        vel.bounce(hit.normal, cfg.game.weak_spot_restitution);
+
+        commands.entity(hit.player).remove::<Dashing>();
+    }
+}
+
+fn nuke_knockback_system(
+    mut nuke_hits: EventReader<NukeHit>,
+    nukes: Query<&Transform, With<Nuke>>,
+    mut enemies: Query<(&Transform, &mut Velocity, &Mass), (With<Enemy>, Without<Nuke>)>,
+    cfg: Res<Config>,
+) {
+    for hit in nuke_hits.read() {
+        let (Ok(nuke_transform), Ok((transform, mut vel, mass))) =
+            (nukes.get(hit.nuke), enemies.get_mut(hit.enemy))
+        else {
+            continue;
+        };
+
+        let dir = (transform.translation - nuke_transform.translation)
+            .truncate()
+            .normalize_or(Vec2::Y);
+
+        vel.apply_impulse(dir * cfg.game.nuke_knockback_impulse, **mass);
+    }
+}
+
+fn stun_system(mut commands: Commands, mut query: Query<(Entity, &mut Stunned)>, time: Res<Time>) {
+    for (entity, mut stunned) in query.iter_mut() {
+        stunned.timer.tick(time.delta());
+        if stunned.timer.finished() {
+            commands.entity(entity).remove::<Stunned>();
+        }
+    }
+}
+
+fn player_hit_blink_system(
+    mut commands: Commands,
+    mut player_hits: EventReader<PlayerHit>,
+    players: Query<&Health, With<Player>>,
+) {
+    for hit in player_hits.read() {
+        if players
+            .get(hit.player)
+            .is_ok_and(|health| health.current > 0)
+        {
+            commands.entity(hit.player).insert(Blink::new(50));
+        }
+    }
+}
+
+/// Start of a swept segment expressed relative to a target that moved from
+/// `target_start` to `target_end` over the same tick.
+fn relative_start(start: Vec2, target_start: Vec2, target_end: Vec2) -> Vec2 {
+    start + (target_end - target_start)
+}
+
 fn collision_immunity_system(
     mut commands: Commands,
     mut query: Query<(Entity, &mut CollisionImmunity, &mut Sprite)>,
@@ -310,3 +838,102 @@ fn blink_system(mut query: Query<(&mut Blink, &mut Sprite)>, time: Res<Time>) {
         sprite.color.set_alpha(alpha);
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use bevy::ecs::system::RunSystemOnce;
+
+    use super::*;
+    use crate::{
+        config::GameConfig,
+        enemy::Turret,
+        health::{enemy_damage_system, EnemyKilled},
+    };
+
+    #[test]
+    fn updrafts_leave_turrets_in_place() {
+        let mut world = World::new();
+        let mut time = Time::<()>::default();
+        time.advance_by(Duration::from_millis(100));
+        world.insert_resource(time);
+        world.insert_resource(RunClock::default());
+        world.insert_resource(Config::new(GameConfig::default()));
+
+        world.spawn((
+            WindZone {
+                half_size: Vec2::splat(100.),
+                direction: Vec2::Y,
+                strength: 200.,
+                noise: 0.,
+            },
+            Transform::default(),
+        ));
+
+        let floating = PhysicsBody {
+            gravity_scale: 0.,
+            ..default()
+        };
+        let turret = world
+            .spawn((
+                Turret::default(),
+                Velocity::default(),
+                floating,
+                Transform::default(),
+            ))
+            .id();
+        let drone = world
+            .spawn((Velocity::default(), floating, Transform::default()))
+            .id();
+
+        for _ in 0..10 {
+            world.run_system_once(wind_system).unwrap();
+            world.run_system_once(apply_velocity_system).unwrap();
+        }
+
+        assert_eq!(
+            world.get::<Transform>(turret).unwrap().translation,
+            Vec3::ZERO
+        );
+        assert!(world.get::<Transform>(drone).unwrap().translation.y > 0.);
+    }
+
+    #[test]
+    fn dashing_onto_a_turret_kills_it() {
+        let mut world = World::new();
+        world.init_resource::<SpatialGrid>();
+        world.init_resource::<Events<EnemyWeakSpotHit>>();
+        world.init_resource::<Events<NukeHit>>();
+        world.init_resource::<Events<EnemyKilled>>();
+
+        let turret = world
+            .spawn((
+                Enemy,
+                Turret::default(),
+                Turret::weak_spot(),
+                Velocity::default(),
+                Collider::circle(16.),
+                Health::new(1),
+                Transform::default(),
+            ))
+            .id();
+
+        // straight down from above, ending inside the body
+        let end = Vec3::new(0., 10., 0.);
+        world.spawn((
+            Player,
+            Collider::circle(8.),
+            DashEffect::new(Vec2::NEG_Y, 1., 0.5),
+            Interpolated {
+                previous: Vec3::new(0., 80., 0.),
+                current: end,
+            },
+            Transform::from_translation(end),
+        ));
+
+        world.run_system_once(rebuild_spatial_grid_system).unwrap();
+        world.run_system_once(weak_spot_collision_system).unwrap();
+        world.run_system_once(enemy_damage_system).unwrap();
+
+        assert!(world.get::<Killed>(turret).is_some());
+    }
+}
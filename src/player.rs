@@ -1,17 +1,22 @@
+use std::time::Duration;
+
 use bevy::{audio, prelude::*, sprite::AlphaMode2d};
 
 use crate::{
-    animation::{Animation, AnimationDir},
+    animation::{Animation, AnimationDir, FlipWithVelocity},
     asset_loader::{AudioAssets, ImageAssets},
+    collider::Collider,
     config::Config,
     game_state::GameState,
     health::Health,
-    input::{Input, MousePos},
-    physics::{Radius, Velocity},
+    input::{Action, ActionState, Aim},
+    layers::CollisionLayers,
+    physics::{CollisionSystems, EnemyWeakSpotHit, MovementSystems, Stunned, Velocity},
     score::Score,
 };
 
 #[derive(Component, Default, Debug)]
+#[require(CollisionLayers = CollisionLayers::PLAYER, DashInput)]
 pub struct Player;
 
 #[derive(Component, Default, Debug)]
@@ -51,6 +56,27 @@ impl Dashing {
     }
 }
 
+/// Dash presses remembered across the lockout that follows a dash.
+#[derive(Component, Default, Debug)]
+pub struct DashInput {
+    /// Whether dash was held on the previous tick.
+    held: bool,
+    /// A press made during the lockout, queued until it ends.
+    buffered: Option<Timer>,
+    /// After a weak spot hit, a buffered press may start a dash despite the lockout.
+    grace: Option<Timer>,
+}
+
+impl DashInput {
+    fn tick(&mut self, delta: Duration) {
+        for timer in [&mut self.buffered, &mut self.grace] {
+            if timer.as_mut().is_some_and(|t| t.tick(delta).finished()) {
+                *timer = None;
+            }
+        }
+    }
+}
+
 #[derive(Component, Default, Debug)]
 pub struct DashDirectionArrow {
     pub direction: Vec2,
@@ -79,6 +105,7 @@ impl DashEffect {
 }
 
 #[derive(Component, Default, Debug)]
+#[require(CollisionLayers = CollisionLayers::PLAYER_PROJECTILE)]
 pub struct Nuke {
     pub timer: Timer,
     pub combo: u32,
@@ -93,31 +120,50 @@ impl Nuke {
     }
 }
 
+/// The player let go of a charged dash.
+#[derive(Event, Debug)]
+pub struct DashReleased;
+
 pub struct PlayerPlugin;
 
 impl Plugin for PlayerPlugin {
     fn build(&self, app: &mut App) {
-        app.add_systems(OnEnter(GameState::Running), spawn_player)
+        app.add_event::<DashReleased>()
+            .add_systems(OnEnter(GameState::Running), spawn_player)
             .add_systems(
-                Update,
+                FixedUpdate,
                 (
                     player_movement_system,
                     player_dash_system,
                     player_dash_effect_system,
+                    dash_input_system,
                     player_start_charge_dash_system,
                     player_charging_dash_system,
                     player_release_dash_system,
-                    dash_arrow_system,
                     player_bounds_system,
                     player_nuke_system,
                     nuke_system,
                 )
                     .chain()
+                    .in_set(MovementSystems::Player)
                     .run_if(in_state(GameState::Running)),
+            )
+            .add_systems(
+                FixedUpdate,
+                // the grace opens on the hit tick and a chained dash starts on
+                // the next one, never depending on how the scheduler orders them
+                dash_chain_grace_system
+                    .in_set(CollisionSystems::React)
+                    .after(MovementSystems::Player),
+            )
+            .add_systems(
+                Update,
+                dash_arrow_system.run_if(in_state(GameState::Running)),
             );
     }
 }
 
+#[allow(clippy::too_many_arguments)]
 fn spawn_player(
     mut commands: Commands,
     mut score: ResMut<Score>,
@@ -126,6 +172,7 @@ fn spawn_player(
     image_assets: Res<ImageAssets>,
     mut meshes: ResMut<Assets<Mesh>>,
     mut materials: ResMut<Assets<ColorMaterial>>,
+    cfg: Res<Config>,
 ) {
     log::info!("Spawning player...");
 
@@ -170,10 +217,11 @@ fn spawn_player(
             Player,
             Velocity::default(),
             Transform::from_translation(Vec3::ZERO),
-            Radius(16.),
-            Health::new(3),
+            Collider::circle(16.),
+            Health::new(cfg.game.player_health),
             sprite,
             animation,
+            FlipWithVelocity,
         ))
         .with_children(|parent| {
             parent.spawn((
@@ -186,15 +234,23 @@ fn spawn_player(
 }
 
 fn player_movement_system(
-    input: Res<Input>,
-    mut player: Query<&mut Velocity, (With<Player>, Without<ChargingDash>, Without<Dashing>)>,
+    actions: Res<ActionState>,
+    mut player: Query<
+        &mut Velocity,
+        (
+            With<Player>,
+            Without<ChargingDash>,
+            Without<Dashing>,
+            Without<Stunned>,
+        ),
+    >,
     time: Res<Time>,
     cfg: Res<Config>,
 ) {
     let dt = time.delta_secs();
 
     if let Ok(mut vel) = player.single_mut() {
-        let input = input.dir();
+        let input = actions.dir();
 
         vel.target.x += input.x * cfg.game.player_x_acceleration * dt;
         vel.target.y += input.y * cfg.game.player_y_acceleration * dt;
@@ -236,23 +292,76 @@ fn player_bounds_system(
     }
 }
 
+fn dash_input_system(
+    actions: Res<ActionState>,
+    mut player: Query<(&mut DashInput, Has<DashEffect>), With<Player>>,
+    time: Res<Time>,
+    cfg: Res<Config>,
+) {
+    if let Ok((mut input, locked)) = player.single_mut() {
+        input.tick(time.delta());
+
+        let pressed = actions.pressed(Action::Dash);
+        let just_pressed = pressed && !input.held;
+        input.held = pressed;
+
+        if just_pressed && locked {
+            input.buffered = Some(Timer::from_seconds(
+                cfg.game.dash_input_buffer,
+                TimerMode::Once,
+            ));
+        }
+    }
+}
+
+fn dash_chain_grace_system(
+    mut weak_spot_hits: EventReader<EnemyWeakSpotHit>,
+    mut players: Query<&mut DashInput, With<Player>>,
+    cfg: Res<Config>,
+) {
+    for hit in weak_spot_hits.read() {
+        if let Ok(mut input) = players.get_mut(hit.player) {
+            input.grace = Some(Timer::from_seconds(
+                cfg.game.dash_chain_grace,
+                TimerMode::Once,
+            ));
+        }
+    }
+}
+
 fn player_start_charge_dash_system(
     mut commands: Commands,
-    input: Res<Input>,
-    mouse_pos: Res<MousePos>,
+    actions: Res<ActionState>,
+    aim: Res<Aim>,
     mut player: Query<
-        (Entity, &Transform, &mut Velocity, &Children),
-        (With<Player>, Without<ChargingDash>, Without<DashEffect>),
+        (
+            Entity,
+            &Transform,
+            &mut Velocity,
+            &mut DashInput,
+            Has<DashEffect>,
+            &Children,
+        ),
+        (With<Player>, Without<ChargingDash>, Without<Stunned>),
     >,
     audio_assets: Res<AudioAssets>,
     mut arrows: Query<&mut DashDirectionArrow>,
 ) {
-    if let Ok((entity, transform, mut vel, children)) = player.single_mut() {
-        if input.dash() {
+    if let Ok((entity, transform, mut vel, mut input, locked, children)) = player.single_mut() {
+        let start = if locked {
+            input.grace.is_some() && input.buffered.is_some()
+        } else {
+            actions.pressed(Action::Dash) || input.buffered.is_some()
+        };
+
+        if start {
+            input.buffered = None;
+            input.grace = None;
+
             vel.target = Vec2::ZERO;
 
             let pos = transform.translation.xy();
-            let dir = (**mouse_pos - pos).normalize_or_zero();
+            let dir = aim.dir_from(pos);
 
             let sound_entity = commands
                 .spawn((
@@ -279,7 +388,7 @@ fn player_start_charge_dash_system(
 }
 
 fn player_charging_dash_system(
-    mouse_pos: Res<MousePos>,
+    aim: Res<Aim>,
     mut player: Query<
         (&Transform, &mut ChargingDash, &Children),
         (With<Player>, With<ChargingDash>),
@@ -292,7 +401,7 @@ fn player_charging_dash_system(
 
     if let Ok((transform, mut charging, children)) = player.single_mut() {
         let pos = transform.translation.xy();
-        let dir = (**mouse_pos - pos).normalize_or_zero();
+        let dir = aim.dir_from(pos);
         let power = dt / cfg.game.player_charging_power_duration;
 
         charging.dir = dir;
@@ -300,7 +409,6 @@ fn player_charging_dash_system(
 
         for &child in children.into_iter() {
             if let Ok(mut arrow) = arrows.get_mut(child) {
-                arrow.direction = dir;
                 arrow.visibility = true;
                 arrow.size += power;
                 break;
@@ -311,17 +419,21 @@ fn player_charging_dash_system(
 
 fn player_release_dash_system(
     mut commands: Commands,
-    input: Res<Input>,
-    mut player: Query<(Entity, &mut ChargingDash, &Children), (With<Player>, With<ChargingDash>)>,
+    actions: Res<ActionState>,
+    mut player: Query<
+        (Entity, &mut ChargingDash, Option<&DashEffect>, &Children),
+        (With<Player>, With<ChargingDash>),
+    >,
     mut arrows: Query<&mut DashDirectionArrow>,
     audio_assets: Res<AudioAssets>,
+    mut dash_released: EventWriter<DashReleased>,
     cfg: Res<Config>,
 ) {
-    if input.dash() {
+    if actions.pressed(Action::Dash) {
         return;
     }
 
-    if let Ok((entity, mut charging, children)) = player.single_mut() {
+    if let Ok((entity, mut charging, chained, children)) = player.single_mut() {
         commands.entity(entity).remove::<ChargingDash>();
 
         if let Some(sound_entity) = charging.sound_entity.take() {
@@ -341,11 +453,19 @@ fn player_release_dash_system(
             charging.dir * dash_power,
             cfg.game.player_dash_duration,
         ));
-        commands.entity(entity).insert(DashEffect::new(
+
+        let mut effect = DashEffect::new(
             charging.dir,
             dash_power,
             cfg.game.player_dash_immunity_duration * dash_power,
-        ));
+        );
+        // a dash chained out of the previous one keeps its combo going
+        if let Some(previous) = chained {
+            effect.combo = previous.combo;
+        }
+        commands.entity(entity).insert(effect);
+
+        dash_released.write(DashReleased);
 
         commands.spawn((
             AudioPlayer(audio_assets.dash_release.clone()),
@@ -361,10 +481,22 @@ fn dash_arrow_system(
         &mut Transform,
         &MeshMaterial2d<ColorMaterial>,
     )>,
+    player: Query<&Transform, (With<Player>, Without<DashDirectionArrow>)>,
+    aim: Res<Aim>,
     mut materials: ResMut<Assets<ColorMaterial>>,
     audio_assets: Res<AudioAssets>,
 ) {
+    let player_pos = player
+        .single()
+        .ok()
+        .map(|transform| transform.translation.xy());
+
     for (mut arrow, mut transform, mat) in arrows.iter_mut() {
+        // follow the aim every frame, between fixed ticks too
+        if let (true, Some(player_pos)) = (arrow.visibility, player_pos) {
+            arrow.direction = aim.dir_from(player_pos);
+        }
+
         let pos = arrow.direction * 32.;
         transform.translation.x = pos.x;
         transform.translation.y = pos.y;
@@ -449,7 +581,7 @@ fn player_nuke_system(
 
             commands.spawn((
                 Nuke::new(1.),
-                Radius(96.),
+                Collider::circle(96.),
                 Transform::from_translation(transform.translation),
                 Mesh2d(mesh),
                 MeshMaterial2d(material),
@@ -0,0 +1,439 @@
+use std::{
+    io::{self, Read},
+    path::Path,
+};
+
+use bevy::prelude::*;
+use rand::{rngs::StdRng, SeedableRng};
+
+use crate::{
+    args::Args,
+    config::{Config, GameConfig},
+    difficulty::Difficulty,
+    game_state::GameState,
+    input::{ActionState, Aim},
+};
+
+const MAGIC: &[u8; 4] = b"HPRP";
+const VERSION: u8 = 3;
+#[cfg(not(target_arch = "wasm32"))]
+const REPLAY_DIR: &str = "replays";
+
+/// Randomness for everything that affects the simulation, reseeded every run.
+#[derive(Resource, Deref, DerefMut)]
+pub struct GameRng(pub StdRng);
+
+impl Default for GameRng {
+    fn default() -> Self {
+        Self(StdRng::seed_from_u64(0))
+    }
+}
+
+/// Simulated time since the run started, advanced once per fixed tick.
+#[derive(Resource, Default, Debug)]
+pub struct RunClock {
+    pub elapsed: f32,
+}
+
+/// Input the simulation saw on one fixed tick.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct ReplayFrame {
+    pub actions: ActionState,
+    pub aim: Aim,
+}
+
+/// The effective config from `tick` on.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ConfigChange {
+    pub tick: u32,
+    pub config: GameConfig,
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Replay {
+    pub tick_rate: f64,
+    pub seed: u64,
+    pub difficulty: Difficulty,
+    /// The config the run started with, then every change made while it ran.
+    pub configs: Vec<ConfigChange>,
+    pub frames: Vec<ReplayFrame>,
+}
+
+#[derive(Resource, Debug)]
+pub enum ReplayMode {
+    /// Records the current run, saved when it ends.
+    Recording(Replay),
+    /// Feeds a recorded run back into the simulation instead of live input.
+    Playback { replay: Replay, tick: usize },
+}
+
+pub struct ReplayPlugin;
+
+impl Plugin for ReplayPlugin {
+    fn build(&self, app: &mut App) {
+        let replay_path = app.world().resource::<Args>().replay.clone();
+
+        let mode = match replay_path.map(|path| (Replay::load(&path), path)) {
+            Some((Ok(replay), _)) => ReplayMode::Playback { replay, tick: 0 },
+            Some((Err(err), path)) => {
+                log::error!("Failed to load replay {}: {err}", path.display());
+                ReplayMode::Recording(Replay::default())
+            }
+            None => ReplayMode::Recording(Replay::default()),
+        };
+
+        app.insert_resource(mode)
+            .init_resource::<GameRng>()
+            .init_resource::<RunClock>()
+            // both lead into a new run, before anything in it is spawned
+            .add_systems(OnExit(GameState::MainMenu), start_run_system)
+            .add_systems(OnExit(GameState::GameOver), start_run_system)
+            .add_systems(
+                OnEnter(GameState::MainMenu),
+                start_playback_system.run_if(is_playing_back),
+            )
+            .add_systems(
+                FixedFirst,
+                replay_tick_system.run_if(in_state(GameState::Running)),
+            )
+            .add_systems(OnEnter(GameState::GameOver), save_replay_system);
+    }
+}
+
+pub fn is_playing_back(mode: Res<ReplayMode>) -> bool {
+    matches!(*mode, ReplayMode::Playback { .. })
+}
+
+fn start_run_system(
+    mut mode: ResMut<ReplayMode>,
+    mut config: ResMut<Config>,
+    difficulty: Res<Difficulty>,
+    mut rng: ResMut<GameRng>,
+    mut clock: ResMut<RunClock>,
+    mut fixed_time: ResMut<Time<Fixed>>,
+) {
+    let seed = match &mut *mode {
+        ReplayMode::Recording(replay) => {
+            *replay = Replay {
+                tick_rate: 1. / fixed_time.timestep().as_secs_f64(),
+                seed: rand::random(),
+                difficulty: *difficulty,
+                configs: vec![ConfigChange {
+                    tick: 0,
+                    config: config.game.clone(),
+                }],
+                frames: Vec::new(),
+            };
+            replay.seed
+        }
+        ReplayMode::Playback { replay, tick } => {
+            *tick = 0;
+            fixed_time.set_timestep_hz(replay.tick_rate);
+            if let Some(change) = replay.configs.first() {
+                config.game = change.config.clone();
+            }
+            replay.seed
+        }
+    };
+
+    rng.0 = StdRng::seed_from_u64(seed);
+    *clock = RunClock::default();
+}
+
+/// Applies the recorded difficulty before the run starts.
+fn start_playback_system(
+    mode: Res<ReplayMode>,
+    mut difficulty: ResMut<Difficulty>,
+    mut next_state: ResMut<NextState<GameState>>,
+) {
+    if let ReplayMode::Playback { replay, .. } = &*mode {
+        *difficulty = replay.difficulty;
+    }
+
+    next_state.set(GameState::Running);
+}
+
+fn replay_tick_system(
+    mut mode: ResMut<ReplayMode>,
+    mut actions: ResMut<ActionState>,
+    mut aim: ResMut<Aim>,
+    mut clock: ResMut<RunClock>,
+    mut config: ResMut<Config>,
+    time: Res<Time>,
+) {
+    clock.elapsed += time.delta_secs();
+
+    match &mut *mode {
+        ReplayMode::Recording(replay) => {
+            // hot reloads and tuning change the simulation mid-run
+            if config.is_changed()
+                && replay
+                    .configs
+                    .last()
+                    .is_none_or(|change| change.config != config.game)
+            {
+                replay.configs.push(ConfigChange {
+                    tick: replay.frames.len() as u32,
+                    config: config.game.clone(),
+                });
+            }
+
+            replay.frames.push(ReplayFrame {
+                actions: *actions,
+                aim: *aim,
+            });
+        }
+        ReplayMode::Playback { replay, tick } => {
+            if let Some(change) = replay
+                .configs
+                .iter()
+                .find(|change| change.tick as usize == *tick)
+            {
+                config.game = change.config.clone();
+            }
+
+            let frame = replay.frames.get(*tick).copied();
+            *tick += 1;
+
+            // past the end the pigeon just lets go of everything
+            *actions = frame.map_or_else(ActionState::default, |frame| frame.actions);
+            if let Some(frame) = frame {
+                *aim = frame.aim;
+            }
+        }
+    }
+}
+
+fn save_replay_system(mode: Res<ReplayMode>) {
+    let ReplayMode::Recording(replay) = &*mode else {
+        return;
+    };
+
+    #[cfg(not(target_arch = "wasm32"))]
+    {
+        let path = Path::new(REPLAY_DIR).join(format!("run-{:016x}.replay", replay.seed));
+
+        match replay.save(&path) {
+            Ok(()) => log::info!("Saved replay to {}", path.display()),
+            Err(err) => log::warn!("Failed to save replay to {}: {err}", path.display()),
+        }
+    }
+
+    #[cfg(target_arch = "wasm32")]
+    let _ = replay;
+}
+
+impl Replay {
+    pub fn load(path: &Path) -> io::Result<Self> {
+        Self::decode(&std::fs::read(path)?)
+    }
+
+    pub fn save(&self, path: &Path) -> io::Result<()> {
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir)?;
+        }
+
+        std::fs::write(path, self.encode())
+    }
+
+    /// Header and configs as JSON, followed by run-length encoded frames, since input rarely
+    /// changes from one tick to the next.
+    pub fn encode(&self) -> Vec<u8> {
+        let mut bytes = Vec::new();
+
+        bytes.extend_from_slice(MAGIC);
+        bytes.push(VERSION);
+        bytes.extend_from_slice(&self.tick_rate.to_le_bytes());
+        bytes.extend_from_slice(&self.seed.to_le_bytes());
+        bytes.push(self.difficulty as u8);
+
+        bytes.extend_from_slice(&(self.configs.len() as u32).to_le_bytes());
+        for change in &self.configs {
+            let config = serde_json::to_vec(&change.config).expect("configs serialize to JSON");
+            bytes.extend_from_slice(&change.tick.to_le_bytes());
+            bytes.extend_from_slice(&(config.len() as u32).to_le_bytes());
+            bytes.extend_from_slice(&config);
+        }
+
+        for run in self.frames.chunk_by(|a, b| a == b) {
+            for chunk in run.chunks(u16::MAX as usize) {
+                bytes.extend_from_slice(&(chunk.len() as u16).to_le_bytes());
+                encode_frame(&mut bytes, &chunk[0]);
+            }
+        }
+
+        bytes
+    }
+
+    pub fn decode(mut bytes: &[u8]) -> io::Result<Self> {
+        let mut magic = [0; 4];
+        bytes.read_exact(&mut magic)?;
+        if &magic != MAGIC {
+            return Err(invalid("not a replay file"));
+        }
+
+        let version = read_u8(&mut bytes)?;
+        if version != VERSION {
+            return Err(invalid(format!("unsupported replay version {version}")));
+        }
+
+        let tick_rate = f64::from_le_bytes(read_array(&mut bytes)?);
+        let seed = u64::from_le_bytes(read_array(&mut bytes)?);
+        let difficulty = read_u8(&mut bytes)?;
+        let difficulty = Difficulty::from_index(difficulty)
+            .ok_or_else(|| invalid(format!("unknown difficulty {difficulty}")))?;
+
+        let config_count = u32::from_le_bytes(read_array(&mut bytes)?);
+        let mut configs = Vec::new();
+        for _ in 0..config_count {
+            let tick = u32::from_le_bytes(read_array(&mut bytes)?);
+            let len = u32::from_le_bytes(read_array(&mut bytes)?) as usize;
+            if len > bytes.len() {
+                return Err(invalid("truncated config"));
+            }
+
+            let (config, rest) = bytes.split_at(len);
+            let config = serde_json::from_slice(config)
+                .map_err(|err| invalid(format!("bad config at tick {tick}: {err}")))?;
+            configs.push(ConfigChange { tick, config });
+            bytes = rest;
+        }
+
+        let mut frames = Vec::new();
+        while !bytes.is_empty() {
+            let count = u16::from_le_bytes(read_array(&mut bytes)?);
+            let frame = decode_frame(&mut bytes)?;
+            frames.extend(std::iter::repeat_n(frame, count as usize));
+        }
+
+        Ok(Self {
+            tick_rate,
+            seed,
+            difficulty,
+            configs,
+            frames,
+        })
+    }
+}
+
+fn encode_frame(bytes: &mut Vec<u8>, frame: &ReplayFrame) {
+    let (kind, aim) = match frame.aim {
+        Aim::Cursor(pos) => (0u8, pos),
+        Aim::Direction(dir) => (1u8, dir),
+    };
+
+    bytes.push(frame.actions.buttons);
+    for value in [frame.actions.movement.x, frame.actions.movement.y] {
+        bytes.extend_from_slice(&value.to_le_bytes());
+    }
+
+    bytes.push(kind);
+    for value in [aim.x, aim.y] {
+        bytes.extend_from_slice(&value.to_le_bytes());
+    }
+}
+
+fn decode_frame(bytes: &mut &[u8]) -> io::Result<ReplayFrame> {
+    let buttons = read_u8(bytes)?;
+    let movement = read_vec2(bytes)?;
+    let kind = read_u8(bytes)?;
+    let aim = read_vec2(bytes)?;
+
+    let aim = match kind {
+        0 => Aim::Cursor(aim),
+        1 => Aim::Direction(aim),
+        _ => return Err(invalid(format!("unknown aim kind {kind}"))),
+    };
+
+    Ok(ReplayFrame {
+        actions: ActionState { buttons, movement },
+        aim,
+    })
+}
+
+fn read_u8(bytes: &mut &[u8]) -> io::Result<u8> {
+    Ok(read_array::<1>(bytes)?[0])
+}
+
+fn read_vec2(bytes: &mut &[u8]) -> io::Result<Vec2> {
+    Ok(Vec2::new(
+        f32::from_le_bytes(read_array(bytes)?),
+        f32::from_le_bytes(read_array(bytes)?),
+    ))
+}
+
+fn read_array<const N: usize>(bytes: &mut &[u8]) -> io::Result<[u8; N]> {
+    let mut array = [0; N];
+    bytes.read_exact(&mut array)?;
+    Ok(array)
+}
+
+fn invalid(message: impl Into<String>) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, message.into())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn frame(buttons: u8, aim: Aim) -> ReplayFrame {
+        ReplayFrame {
+            actions: ActionState {
+                buttons,
+                movement: Vec2::new(0.5, -1.),
+            },
+            aim,
+        }
+    }
+
+    #[test]
+    fn round_trips_through_bytes() {
+        let replay = Replay {
+            tick_rate: 64.,
+            seed: 0xdead_beef,
+            difficulty: Difficulty::Hard,
+            configs: vec![
+                ConfigChange {
+                    tick: 0,
+                    config: GameConfig::default(),
+                },
+                ConfigChange {
+                    tick: 2,
+                    config: GameConfig {
+                        gravity: -12.5,
+                        ..default()
+                    },
+                },
+            ],
+            frames: vec![
+                frame(0, Aim::Direction(Vec2::X)),
+                frame(0, Aim::Direction(Vec2::X)),
+                frame(0b10001, Aim::Cursor(Vec2::new(12.5, -40.))),
+                frame(0, Aim::Direction(Vec2::X)),
+            ],
+        };
+
+        assert_eq!(Replay::decode(&replay.encode()).unwrap(), replay);
+    }
+
+    #[test]
+    fn repeated_frames_are_stored_once() {
+        let idle = Replay {
+            tick_rate: 64.,
+            seed: 1,
+            frames: vec![frame(0, Aim::default()); 100_000],
+            ..default()
+        };
+
+        let bytes = idle.encode();
+        // header, no configs, then two runs, the first one full
+        assert_eq!(bytes.len(), 4 + 1 + 8 + 8 + 1 + 4 + 2 * (2 + 18));
+        assert_eq!(Replay::decode(&bytes).unwrap().frames.len(), 100_000);
+    }
+
+    #[test]
+    fn rejects_other_files() {
+        assert!(Replay::decode(b"not a replay").is_err());
+        assert!(Replay::decode(&MAGIC[..2]).is_err());
+    }
+}
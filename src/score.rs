@@ -1,5 +1,11 @@
 use bevy::prelude::*;
 
+use crate::{
+    health::EnemyKilled,
+    physics::{weak_spot_rebound_system, CollisionSystems},
+    player::{DashEffect, Nuke},
+};
+
 #[derive(Resource, Default, Debug)]
 pub struct Score(pub u32);
 
@@ -7,6 +13,33 @@ pub struct ScorePlugin;
 
 impl Plugin for ScorePlugin {
     fn build(&self, app: &mut App) {
-        app.init_resource::<Score>();
+        app.init_resource::<Score>().add_systems(
+            FixedUpdate,
+            score_kill_system
+                .in_set(CollisionSystems::React)
+                .after(weak_spot_rebound_system),
+        );
+    }
+}
+
+/// Each kill is worth one point plus the combo of the dash or nuke that made it.
+fn score_kill_system(
+    mut score: ResMut<Score>,
+    mut enemy_killed: EventReader<EnemyKilled>,
+    mut dashes: Query<&mut DashEffect>,
+    mut nukes: Query<&mut Nuke>,
+) {
+    for kill in enemy_killed.read() {
+        let combo = if let Ok(mut dash) = dashes.get_mut(kill.killer) {
+            dash.combo += 1;
+            dash.combo - 1
+        } else if let Ok(mut nuke) = nukes.get_mut(kill.killer) {
+            nuke.combo += 1;
+            nuke.combo - 1
+        } else {
+            0
+        };
+
+        score.0 += 1 + combo;
     }
 }
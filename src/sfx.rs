@@ -0,0 +1,53 @@
+use bevy::{audio, prelude::*};
+
+use crate::{
+    asset_loader::AudioAssets,
+    health::{EnemyKilled, PlayerDied},
+    physics::{CollisionSystems, PlayerHit},
+};
+
+pub struct SfxPlugin;
+
+impl Plugin for SfxPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            FixedUpdate,
+            (player_hit_sfx_system, enemy_killed_sfx_system).in_set(CollisionSystems::React),
+        );
+    }
+}
+
+fn player_hit_sfx_system(
+    mut commands: Commands,
+    mut player_hits: EventReader<PlayerHit>,
+    mut player_died: EventReader<PlayerDied>,
+    audio_assets: Res<AudioAssets>,
+) {
+    let sound = if player_died.read().next().is_some() {
+        player_hits.clear();
+        audio_assets.player_death.clone()
+    } else if player_hits.read().next().is_some() {
+        player_hits.clear();
+        audio_assets.player_hit.clone()
+    } else {
+        return;
+    };
+
+    commands.spawn((
+        AudioPlayer(sound),
+        PlaybackSettings::REMOVE.with_volume(audio::Volume::Linear(1.)),
+    ));
+}
+
+fn enemy_killed_sfx_system(
+    mut commands: Commands,
+    mut enemy_killed: EventReader<EnemyKilled>,
+    audio_assets: Res<AudioAssets>,
+) {
+    for _ in enemy_killed.read() {
+        commands.spawn((
+            AudioPlayer(audio_assets.enemy_death.clone()),
+            PlaybackSettings::REMOVE.with_volume(audio::Volume::Linear(1.)),
+        ));
+    }
+}
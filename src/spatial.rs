@@ -0,0 +1,122 @@
+use bevy::{platform::collections::HashMap, prelude::*};
+
+/// Uniform-grid broadphase, rebuilt every tick from the current colliders.
+///
+/// Entries are bucketed into every cell their bounds overlap, so a query only
+/// visits the cells around the queried area instead of every collider.
+#[derive(Resource, Debug)]
+pub struct SpatialGrid {
+    cell_size: f32,
+    cells: HashMap<IVec2, Vec<Entity>>,
+}
+
+impl Default for SpatialGrid {
+    fn default() -> Self {
+        Self::new(64.)
+    }
+}
+
+impl SpatialGrid {
+    pub fn new(cell_size: f32) -> Self {
+        Self {
+            cell_size,
+            cells: HashMap::default(),
+        }
+    }
+
+    /// Empties every cell, keeping the ones used since the last clear for reuse
+    /// and dropping the rest, so the map doesn't grow with every cell anything
+    /// ever passed through.
+    pub fn clear(&mut self) {
+        self.cells.retain(|_, cell| {
+            let used = !cell.is_empty();
+            cell.clear();
+            used
+        });
+    }
+
+    pub fn insert(&mut self, entity: Entity, min: Vec2, max: Vec2) {
+        let (min_cell, max_cell) = self.cell_range(min, max);
+
+        for y in min_cell.y..=max_cell.y {
+            for x in min_cell.x..=max_cell.x {
+                self.cells.entry(IVec2::new(x, y)).or_default().push(entity);
+            }
+        }
+    }
+
+    /// Entities whose bounds share a cell with `min..max`, without duplicates.
+    pub fn query(&self, min: Vec2, max: Vec2) -> Vec<Entity> {
+        let (min_cell, max_cell) = self.cell_range(min, max);
+        let mut found = Vec::new();
+
+        for y in min_cell.y..=max_cell.y {
+            for x in min_cell.x..=max_cell.x {
+                if let Some(cell) = self.cells.get(&IVec2::new(x, y)) {
+                    found.extend_from_slice(cell);
+                }
+            }
+        }
+
+        found.sort_unstable();
+        found.dedup();
+        found
+    }
+
+    fn cell_range(&self, min: Vec2, max: Vec2) -> (IVec2, IVec2) {
+        let min_cell = (min / self.cell_size).floor().as_ivec2();
+        let max_cell = (max / self.cell_size).floor().as_ivec2();
+        (min_cell, max_cell)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entity(index: u32) -> Entity {
+        Entity::from_raw(index)
+    }
+
+    #[test]
+    fn finds_bounds_sharing_a_cell_once() {
+        let mut grid = SpatialGrid::new(10.);
+        // spans four cells around the origin
+        grid.insert(entity(0), Vec2::splat(-5.), Vec2::splat(5.));
+        grid.insert(entity(1), Vec2::new(12., 12.), Vec2::new(14., 14.));
+        grid.insert(entity(2), Vec2::new(-35., 0.), Vec2::new(-31., 4.));
+
+        assert_eq!(
+            grid.query(Vec2::splat(-1.), Vec2::splat(15.)),
+            vec![entity(0), entity(1)]
+        );
+        assert_eq!(
+            grid.query(Vec2::new(-9., -9.), Vec2::new(-8., -8.)),
+            vec![entity(0)]
+        );
+        assert_eq!(
+            grid.query(Vec2::new(-38., 0.), Vec2::new(-32., 2.)),
+            vec![entity(2)]
+        );
+        assert!(grid.query(Vec2::splat(50.), Vec2::splat(60.)).is_empty());
+    }
+
+    #[test]
+    fn clear_empties_and_prunes_unused_cells() {
+        let mut grid = SpatialGrid::new(10.);
+        grid.insert(entity(0), Vec2::ZERO, Vec2::splat(25.));
+        assert_eq!(grid.cells.len(), 9);
+
+        grid.clear();
+        assert!(grid.query(Vec2::ZERO, Vec2::splat(25.)).is_empty());
+        // kept for the next tick
+        assert_eq!(grid.cells.len(), 9);
+
+        grid.insert(entity(1), Vec2::ZERO, Vec2::ONE);
+        grid.clear();
+        assert_eq!(grid.cells.len(), 1);
+
+        grid.clear();
+        assert!(grid.cells.is_empty());
+    }
+}
@@ -0,0 +1,187 @@
+use bevy::prelude::*;
+use serde::Serialize;
+
+use crate::{
+    difficulty::Difficulty,
+    game_state::GameState,
+    health::{EnemyKilled, PlayerDamaged},
+    physics::{CollisionSystems, EnemyWeakSpotHit},
+    player::{DashEffect, DashReleased, Nuke},
+    replay::{ReplayMode, RunClock},
+    score::Score,
+};
+
+#[cfg(not(target_arch = "wasm32"))]
+const STATS_DIR: &str = "stats";
+
+/// How the current or last run went, for the game over screen and balancing.
+#[derive(Resource, Clone, Debug, Default, PartialEq, Serialize)]
+pub struct RunStats {
+    pub difficulty: Difficulty,
+    pub seed: u64,
+    pub score: u32,
+    /// In simulated seconds.
+    pub time_survived: f32,
+    pub dash_kills: u32,
+    pub nuke_kills: u32,
+    /// Most kills out of a single dash chain or nuke.
+    pub max_combo: u32,
+    pub dashes_fired: u32,
+    /// Every weak spot reached, several per dash when it chains.
+    pub weak_spot_hits: u32,
+    /// Dashes that reached at least one weak spot.
+    pub dashes_landed: u32,
+    /// `dashes_landed / dashes_fired`, zero before the first dash.
+    pub weak_spot_accuracy: f32,
+    pub damage_taken: u32,
+    /// Stays 0 until antennas can be hacked, but keeps the column in exports.
+    pub antennas_hacked: u32,
+    /// `dashes_fired` as of the last dash that landed, which tells dashes apart.
+    #[serde(skip)]
+    landed_dash: Option<u32>,
+}
+
+pub struct StatsPlugin;
+
+impl Plugin for StatsPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<RunStats>()
+            .add_systems(OnEnter(GameState::Running), start_run_stats_system)
+            .add_systems(
+                FixedUpdate,
+                (
+                    count_events_system.in_set(CollisionSystems::React),
+                    update_run_stats_system.after(CollisionSystems::React),
+                )
+                    .run_if(in_state(GameState::Running)),
+            );
+    }
+}
+
+impl RunStats {
+    /// Writes the stats to `stats/run-<seed>.json` natively; on the web the
+    /// browser downloads them under the same name.
+    pub fn export(&self) {
+        let json = match serde_json::to_string_pretty(self) {
+            Ok(json) => json,
+            Err(err) => {
+                log::warn!("Failed to export run stats: {err}");
+                return;
+            }
+        };
+        let file_name = format!("run-{:016x}.json", self.seed);
+
+        #[cfg(not(target_arch = "wasm32"))]
+        {
+            let path = std::path::Path::new(STATS_DIR).join(file_name);
+
+            let result =
+                std::fs::create_dir_all(STATS_DIR).and_then(|()| std::fs::write(&path, json));
+
+            match result {
+                Ok(()) => log::info!("Exported run stats to {}", path.display()),
+                Err(err) => log::warn!("Failed to export run stats to {}: {err}", path.display()),
+            }
+        }
+
+        #[cfg(target_arch = "wasm32")]
+        match download(&file_name, &json) {
+            Ok(()) => log::info!("Exported run stats as {file_name}"),
+            Err(err) => log::warn!("Failed to export run stats: {err:?}"),
+        }
+    }
+}
+
+/// Hands `contents` to the browser as a file download, through a temporary
+/// link to a blob.
+#[cfg(target_arch = "wasm32")]
+fn download(file_name: &str, contents: &str) -> Result<(), wasm_bindgen::JsValue> {
+    use wasm_bindgen::JsCast;
+
+    let options = web_sys::BlobPropertyBag::new();
+    options.set_type("application/json");
+    let blob = web_sys::Blob::new_with_str_sequence_and_options(
+        &js_sys::Array::of1(&contents.into()),
+        &options,
+    )?;
+    let url = web_sys::Url::create_object_url_with_blob(&blob)?;
+
+    let document = web_sys::window()
+        .and_then(|window| window.document())
+        .ok_or("no document to download from")?;
+    let link: web_sys::HtmlAnchorElement = document.create_element("a")?.dyn_into()?;
+    link.set_href(&url);
+    link.set_download(file_name);
+    link.click();
+
+    web_sys::Url::revoke_object_url(&url)
+}
+
+fn start_run_stats_system(
+    mut stats: ResMut<RunStats>,
+    difficulty: Res<Difficulty>,
+    mode: Res<ReplayMode>,
+) {
+    let seed = match &*mode {
+        ReplayMode::Recording(replay) | ReplayMode::Playback { replay, .. } => replay.seed,
+    };
+
+    *stats = RunStats {
+        difficulty: *difficulty,
+        seed,
+        ..default()
+    };
+}
+
+fn count_events_system(
+    mut stats: ResMut<RunStats>,
+    mut dash_released: EventReader<DashReleased>,
+    mut weak_spot_hits: EventReader<EnemyWeakSpotHit>,
+    mut enemy_killed: EventReader<EnemyKilled>,
+    mut player_damaged: EventReader<PlayerDamaged>,
+    nukes: Query<(), With<Nuke>>,
+) {
+    stats.dashes_fired += dash_released.read().count() as u32;
+
+    let hits = weak_spot_hits.read().count() as u32;
+    stats.weak_spot_hits += hits;
+
+    if hits > 0 && stats.landed_dash != Some(stats.dashes_fired) {
+        stats.landed_dash = Some(stats.dashes_fired);
+        stats.dashes_landed += 1;
+    }
+
+    for kill in enemy_killed.read() {
+        if nukes.contains(kill.killer) {
+            stats.nuke_kills += 1;
+        } else {
+            stats.dash_kills += 1;
+        }
+    }
+
+    stats.damage_taken += player_damaged.read().count() as u32;
+}
+
+fn update_run_stats_system(
+    mut stats: ResMut<RunStats>,
+    score: Res<Score>,
+    clock: Res<RunClock>,
+    dashes: Query<&DashEffect>,
+    nukes: Query<&Nuke>,
+) {
+    let combo = dashes
+        .iter()
+        .map(|dash| dash.combo)
+        .chain(nukes.iter().map(|nuke| nuke.combo))
+        .max()
+        .unwrap_or(0);
+
+    stats.score = score.0;
+    stats.time_survived = clock.elapsed;
+    stats.max_combo = stats.max_combo.max(combo);
+    stats.weak_spot_accuracy = if stats.dashes_fired == 0 {
+        0.
+    } else {
+        stats.dashes_landed as f32 / stats.dashes_fired as f32
+    };
+}
@@ -0,0 +1,78 @@
+use std::io;
+
+use serde::{de::DeserializeOwned, Serialize};
+
+/// Directory holding saved data on native builds, relative to the working directory.
+#[cfg(not(target_arch = "wasm32"))]
+const SAVE_DIR: &str = "save";
+
+/// Loads a value saved under `key`. Missing data yields `None`; unreadable data is
+/// logged and ignored so callers can fall back to defaults.
+pub fn load<T: DeserializeOwned>(key: &str) -> Option<T> {
+    let contents = match read(key) {
+        Ok(contents) => contents?,
+        Err(err) => {
+            log::warn!("Failed to read saved {key}: {err}");
+            return None;
+        }
+    };
+
+    match ron::from_str(&contents) {
+        Ok(value) => Some(value),
+        Err(err) => {
+            log::warn!("Ignoring corrupt saved {key}: {err}");
+            None
+        }
+    }
+}
+
+pub fn save<T: Serialize>(key: &str, value: &T) {
+    let result = ron::ser::to_string_pretty(value, ron::ser::PrettyConfig::default())
+        .map_err(io::Error::other)
+        .and_then(|contents| write(key, &contents));
+
+    if let Err(err) = result {
+        log::warn!("Failed to save {key}: {err}");
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+fn path(key: &str) -> std::path::PathBuf {
+    std::path::Path::new(SAVE_DIR).join(format!("{key}.ron"))
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+fn read(key: &str) -> io::Result<Option<String>> {
+    match std::fs::read_to_string(path(key)) {
+        Ok(contents) => Ok(Some(contents)),
+        Err(err) if err.kind() == io::ErrorKind::NotFound => Ok(None),
+        Err(err) => Err(err),
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+fn write(key: &str, contents: &str) -> io::Result<()> {
+    std::fs::create_dir_all(SAVE_DIR)?;
+    std::fs::write(path(key), contents)
+}
+
+#[cfg(target_arch = "wasm32")]
+fn local_storage() -> io::Result<web_sys::Storage> {
+    web_sys::window()
+        .and_then(|window| window.local_storage().ok().flatten())
+        .ok_or_else(|| io::Error::other("localStorage is unavailable"))
+}
+
+#[cfg(target_arch = "wasm32")]
+fn read(key: &str) -> io::Result<Option<String>> {
+    local_storage()?
+        .get_item(key)
+        .map_err(|err| io::Error::other(format!("{err:?}")))
+}
+
+#[cfg(target_arch = "wasm32")]
+fn write(key: &str, contents: &str) -> io::Result<()> {
+    local_storage()?
+        .set_item(key, contents)
+        .map_err(|err| io::Error::other(format!("{err:?}")))
+}
@@ -0,0 +1,201 @@
+use bevy::{input::touch::TouchPhase, prelude::*};
+
+/// Joystick travel in logical pixels for full movement speed.
+pub const JOYSTICK_RADIUS: f32 = 60.;
+/// Drag distance in logical pixels before an aim touch counts as aiming.
+const AIM_DEADZONE: f32 = 12.;
+
+/// A finger held on screen, in logical window pixels.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct TouchDrag {
+    pub id: u64,
+    pub origin: Vec2,
+    pub current: Vec2,
+}
+
+impl TouchDrag {
+    fn new(id: u64, position: Vec2) -> Self {
+        Self {
+            id,
+            origin: position,
+            current: position,
+        }
+    }
+
+    /// Drag offset in world orientation (y up).
+    fn offset(&self) -> Vec2 {
+        let offset = self.current - self.origin;
+        Vec2::new(offset.x, -offset.y)
+    }
+}
+
+/// On-screen controls: a virtual joystick on the left half of the screen and
+/// drag-to-aim, release-to-dash on the right half.
+#[derive(Resource, Default, Debug)]
+pub struct TouchControls {
+    /// Set once any touch is seen; the overlay only shows from then on.
+    pub enabled: bool,
+    pub joystick: Option<TouchDrag>,
+    pub aim: Option<TouchDrag>,
+}
+
+impl TouchControls {
+    pub fn movement(&self) -> Vec2 {
+        self.joystick.map_or(Vec2::ZERO, |drag| {
+            (drag.offset() / JOYSTICK_RADIUS).clamp_length_max(1.)
+        })
+    }
+
+    /// Dash charges while the aim finger is down and fires when it lifts.
+    pub fn dash_held(&self) -> bool {
+        self.aim.is_some()
+    }
+
+    pub fn aim_dir(&self) -> Option<Vec2> {
+        self.aim
+            .map(|drag| drag.offset())
+            .filter(|offset| offset.length() > AIM_DEADZONE)
+            .map(Vec2::normalize)
+    }
+}
+
+pub struct TouchPlugin;
+
+impl Plugin for TouchPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<TouchControls>()
+            .add_systems(PreUpdate, read_touches);
+    }
+}
+
+fn read_touches(
+    mut touches: EventReader<TouchInput>,
+    windows: Query<&Window>,
+    mut controls: ResMut<TouchControls>,
+) {
+    for touch in touches.read() {
+        let Ok(window) = windows.get(touch.window) else {
+            continue;
+        };
+
+        match touch.phase {
+            TouchPhase::Started => {
+                controls.enabled = true;
+
+                let drag = Some(TouchDrag::new(touch.id, touch.position));
+
+                if touch.position.x < window.width() / 2. {
+                    if controls.joystick.is_none() {
+                        controls.joystick = drag;
+                    }
+                } else if controls.aim.is_none() {
+                    controls.aim = drag;
+                }
+            }
+            TouchPhase::Moved => {
+                let controls = &mut *controls;
+
+                for drag in [&mut controls.joystick, &mut controls.aim]
+                    .into_iter()
+                    .flatten()
+                    .filter(|drag| drag.id == touch.id)
+                {
+                    drag.current = touch.position;
+                }
+            }
+            TouchPhase::Ended | TouchPhase::Canceled => {
+                if controls.joystick.is_some_and(|drag| drag.id == touch.id) {
+                    controls.joystick = None;
+                }
+                if controls.aim.is_some_and(|drag| drag.id == touch.id) {
+                    controls.aim = None;
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bevy::window::WindowResolution;
+    use TouchPhase::*;
+
+    fn app() -> App {
+        let mut app = App::new();
+        app.add_event::<TouchInput>()
+            .init_resource::<TouchControls>()
+            .add_systems(Update, read_touches);
+
+        app.world_mut().spawn(Window {
+            resolution: WindowResolution::new(800., 600.),
+            ..default()
+        });
+
+        app
+    }
+
+    fn touch(app: &mut App, id: u64, phase: TouchPhase, x: f32, y: f32) -> &TouchControls {
+        let window = app
+            .world_mut()
+            .query_filtered::<Entity, With<Window>>()
+            .single(app.world())
+            .unwrap();
+
+        app.world_mut().send_event(TouchInput {
+            phase,
+            position: Vec2::new(x, y),
+            window,
+            force: None,
+            id,
+        });
+        app.update();
+
+        app.world().resource::<TouchControls>()
+    }
+
+    #[test]
+    fn joystick_moves_with_analog_magnitude() {
+        let mut app = app();
+
+        touch(&mut app, 1, Started, 100., 400.);
+        let controls = touch(&mut app, 1, Moved, 130., 400.);
+        assert!(controls.enabled);
+        assert_eq!(controls.movement(), Vec2::new(0.5, 0.));
+        assert!(!controls.dash_held());
+
+        let controls = touch(&mut app, 1, Moved, 100., 200.);
+        assert_eq!(controls.movement(), Vec2::Y);
+
+        let controls = touch(&mut app, 1, Ended, 100., 200.);
+        assert_eq!(controls.movement(), Vec2::ZERO);
+    }
+
+    #[test]
+    fn aim_drag_charges_and_release_dashes() {
+        let mut app = app();
+
+        let controls = touch(&mut app, 7, Started, 600., 300.);
+        assert!(controls.dash_held());
+        assert_eq!(controls.aim_dir(), None);
+
+        let controls = touch(&mut app, 7, Moved, 600., 340.);
+        assert_eq!(controls.aim_dir(), Some(Vec2::NEG_Y));
+
+        let controls = touch(&mut app, 7, Ended, 600., 340.);
+        assert!(!controls.dash_held());
+    }
+
+    #[test]
+    fn both_halves_track_separate_fingers() {
+        let mut app = app();
+
+        touch(&mut app, 1, Started, 100., 400.);
+        touch(&mut app, 2, Started, 700., 300.);
+        touch(&mut app, 2, Moved, 760., 300.);
+        let controls = touch(&mut app, 1, Moved, 40., 400.);
+
+        assert_eq!(controls.movement(), Vec2::NEG_X);
+        assert_eq!(controls.aim_dir(), Some(Vec2::X));
+    }
+}
@@ -0,0 +1,180 @@
+use bevy::color::palettes::tailwind;
+use bevy::prelude::*;
+
+use crate::{
+    game_state::GameState,
+    input::{Action, ActionMap, Binding},
+};
+
+#[derive(Component)]
+pub struct ControlsUI;
+
+#[derive(Component, Clone, Copy)]
+pub enum ControlsButton {
+    Rebind(Action),
+    Reset,
+    Back,
+}
+
+/// Text listing the bindings of an action.
+#[derive(Component)]
+pub struct BindingsText(Action);
+
+/// The action waiting for its next binding.
+#[derive(Resource)]
+pub struct Rebinding(Action);
+
+pub fn setup_controls_ui(mut commands: Commands) {
+    commands
+        .spawn((
+            ControlsUI,
+            Node {
+                justify_content: JustifyContent::Center,
+                align_items: AlignItems::Center,
+                flex_direction: FlexDirection::Column,
+                row_gap: Val::Px(12.),
+                width: Val::Percent(100.),
+                height: Val::Percent(100.),
+                ..default()
+            },
+            BackgroundColor(tailwind::GRAY_900.into()),
+        ))
+        .with_children(|parent| {
+            parent.spawn(label("Controls", 40.));
+
+            for action in Action::ALL {
+                parent
+                    .spawn(Node {
+                        column_gap: Val::Px(16.),
+                        align_items: AlignItems::Center,
+                        ..default()
+                    })
+                    .with_children(|row| {
+                        row.spawn((
+                            label(action.label(), 20.),
+                            Node {
+                                width: Val::Px(80.),
+                                ..default()
+                            },
+                        ));
+                        row.spawn(button(ControlsButton::Rebind(action)))
+                            .with_child((BindingsText(action), label("", 20.)));
+                    });
+            }
+
+            parent
+                .spawn(Node {
+                    column_gap: Val::Px(16.),
+                    margin: UiRect::top(Val::Px(16.)),
+                    ..default()
+                })
+                .with_children(|row| {
+                    row.spawn(button(ControlsButton::Reset))
+                        .with_child(label("Reset", 20.));
+                    row.spawn(button(ControlsButton::Back))
+                        .with_child(label("Back", 20.));
+                });
+        });
+}
+
+fn label(text: &str, font_size: f32) -> impl Bundle + use<> {
+    (
+        Text::new(text),
+        TextFont {
+            font_size,
+            ..default()
+        },
+        TextColor(tailwind::GRAY_200.into()),
+    )
+}
+
+fn button(kind: ControlsButton) -> impl Bundle {
+    (
+        Button,
+        kind,
+        Node {
+            padding: UiRect::all(Val::Px(8.0)),
+            justify_content: JustifyContent::Center,
+            align_items: AlignItems::Center,
+            min_width: Val::Px(120.),
+            ..default()
+        },
+        BackgroundColor(tailwind::BLUE_700.into()),
+    )
+}
+
+pub fn controls_buttons(
+    mut commands: Commands,
+    interaction_query: Query<(&Interaction, &ControlsButton), Changed<Interaction>>,
+    mut map: ResMut<ActionMap>,
+    mut next_state: ResMut<NextState<GameState>>,
+) {
+    for (interaction, button) in interaction_query.iter() {
+        if *interaction != Interaction::Pressed {
+            continue;
+        }
+
+        match *button {
+            ControlsButton::Rebind(action) => commands.insert_resource(Rebinding(action)),
+            ControlsButton::Reset => {
+                *map = ActionMap::default();
+                map.save();
+            }
+            ControlsButton::Back => next_state.set(GameState::MainMenu),
+        }
+    }
+}
+
+/// Binds the waiting action to the next button pressed on any device.
+pub fn capture_binding(
+    mut commands: Commands,
+    rebinding: Option<Res<Rebinding>>,
+    keys: Res<ButtonInput<KeyCode>>,
+    mouse: Res<ButtonInput<MouseButton>>,
+    gamepads: Query<&Gamepad>,
+    mut map: ResMut<ActionMap>,
+) {
+    // skip the click that started rebinding
+    let Some(rebinding) = rebinding.filter(|rebinding| !rebinding.is_added()) else {
+        return;
+    };
+
+    if keys.just_pressed(KeyCode::Escape) {
+        commands.remove_resource::<Rebinding>();
+        return;
+    }
+
+    if let Some(binding) = Binding::just_pressed(&keys, &mouse, &gamepads) {
+        map.rebind(rebinding.0, binding);
+        map.save();
+        commands.remove_resource::<Rebinding>();
+    }
+}
+
+pub fn update_bindings_text(
+    map: Res<ActionMap>,
+    rebinding: Option<Res<Rebinding>>,
+    mut texts: Query<(&BindingsText, &mut Text)>,
+) {
+    for (bindings, mut text) in texts.iter_mut() {
+        let action = bindings.0;
+
+        text.0 = if rebinding.as_ref().is_some_and(|r| r.0 == action) {
+            "Press a button...".to_string()
+        } else {
+            map.bindings(action)
+                .iter()
+                .map(ToString::to_string)
+                .collect::<Vec<_>>()
+                .join(", ")
+        };
+    }
+}
+
+pub fn cleanup_controls_ui(mut commands: Commands, query: Query<Entity, With<ControlsUI>>) {
+    commands.remove_resource::<Rebinding>();
+
+    for entity in query.iter() {
+        commands.entity(entity).despawn();
+    }
+}
@@ -1,78 +1,320 @@
 use bevy::color::palettes::tailwind;
+use bevy::input::keyboard::{Key, KeyboardInput};
 use bevy::prelude::*;
 
-use crate::{game_state::GameState, score::Score};
+use crate::{
+    difficulty::Difficulty,
+    game_state::GameState,
+    high_score::{sanitize_name, today, HighScore, HighScores, NAME_LENGTH},
+    replay::ReplayMode,
+    score::Score,
+    stats::RunStats,
+};
 
 #[derive(Component)]
 pub struct GameOverUI;
 
-pub fn setup_gameover_ui(mut commands: Commands, score: Res<Score>) {
-    commands.spawn((
-        GameOverUI,
+#[derive(Component, Clone, Copy)]
+pub enum GameOverButton {
+    PlayAgain,
+    /// Cycles through the alphabet at this position of the name.
+    Letter(usize),
+    SaveScore,
+    ExportStats,
+}
+
+/// Shown while a new high score waits for a name.
+#[derive(Component)]
+pub struct NameEntryUI;
+
+#[derive(Component)]
+pub struct LetterText(usize);
+
+/// A run that made the high-score table, still missing its name.
+#[derive(Resource)]
+pub struct NameEntry(HighScore);
+
+pub fn setup_gameover_ui(
+    mut commands: Commands,
+    score: Res<Score>,
+    difficulty: Res<Difficulty>,
+    high_scores: Res<HighScores>,
+    mode: Res<ReplayMode>,
+    stats: Res<RunStats>,
+) {
+    // played back runs were already scored when they were recorded
+    let entry = match &*mode {
+        ReplayMode::Recording(replay) if high_scores.qualifies(score.0, *difficulty) => {
+            Some(HighScore {
+                name: String::new(),
+                score: score.0,
+                date: today(),
+                difficulty: *difficulty,
+                seed: replay.seed,
+            })
+        }
+        _ => None,
+    };
+
+    commands
+        .spawn((
+            GameOverUI,
+            Node {
+                justify_content: JustifyContent::Center,
+                align_items: AlignItems::Center,
+                display: Display::Flex,
+                flex_direction: FlexDirection::Column,
+                row_gap: Val::Px(16.),
+                padding: UiRect::all(Val::Px(16.)),
+                width: Val::Percent(100.),
+                height: Val::Percent(100.),
+                ..default()
+            },
+            BackgroundColor(tailwind::GRAY_900.with_alpha(0.8).into()),
+        ))
+        .with_children(|parent| {
+            parent.spawn(label("GAME OVER", 40., tailwind::GRAY_200));
+            parent.spawn(label(
+                &format!("Final Score: {}", score.0),
+                40.,
+                tailwind::GRAY_200,
+            ));
+            parent.spawn(label(
+                &format!("Difficulty: {}", difficulty.label()),
+                20.,
+                tailwind::GRAY_400,
+            ));
+
+            if entry.is_some() {
+                parent
+                    .spawn((
+                        NameEntryUI,
+                        Node {
+                            flex_direction: FlexDirection::Column,
+                            align_items: AlignItems::Center,
+                            row_gap: Val::Px(8.),
+                            ..default()
+                        },
+                    ))
+                    .with_children(|entry| {
+                        entry.spawn(label(
+                            "New high score! Enter your name:",
+                            20.,
+                            tailwind::YELLOW_400,
+                        ));
+                        entry
+                            .spawn(Node {
+                                column_gap: Val::Px(8.),
+                                ..default()
+                            })
+                            .with_children(|row| {
+                                for index in 0..NAME_LENGTH {
+                                    row.spawn(button(GameOverButton::Letter(index)))
+                                        .with_child((
+                                            LetterText(index),
+                                            label("_", 32., tailwind::GRAY_200),
+                                        ));
+                                }
+                                row.spawn(button(GameOverButton::SaveScore))
+                                    .with_child(label("Save", 20., tailwind::GRAY_200));
+                            });
+                    });
+            }
+
+            parent
+                .spawn(Node {
+                    display: Display::Grid,
+                    grid_template_columns: vec![GridTrack::auto(), GridTrack::auto()],
+                    column_gap: Val::Px(24.),
+                    row_gap: Val::Px(4.),
+                    ..default()
+                })
+                .with_children(|grid| {
+                    for (name, value) in breakdown(&stats) {
+                        grid.spawn(label(name, 16., tailwind::GRAY_400));
+                        grid.spawn(label(&value, 16., tailwind::GRAY_200));
+                    }
+                });
+
+            parent
+                .spawn(Node {
+                    column_gap: Val::Px(16.),
+                    margin: UiRect::top(Val::Auto),
+                    ..default()
+                })
+                .with_children(|row| {
+                    row.spawn(button(GameOverButton::PlayAgain))
+                        .with_child(label("Click to Play Again", 20., tailwind::GRAY_200));
+                    row.spawn(button(GameOverButton::ExportStats))
+                        .with_child(label("Export Stats", 20., tailwind::GRAY_200));
+                });
+        });
+
+    if let Some(entry) = entry {
+        commands.insert_resource(NameEntry(entry));
+    }
+}
+
+fn breakdown(stats: &RunStats) -> [(&'static str, String); 9] {
+    let seconds = stats.time_survived;
+    let accuracy = if stats.dashes_fired == 0 {
+        "-".to_string()
+    } else {
+        format!("{:.0}%", stats.weak_spot_accuracy * 100.)
+    };
+
+    [
+        (
+            "Time survived",
+            format!("{}:{:04.1}", (seconds / 60.) as u32, seconds % 60.),
+        ),
+        ("Dash kills", stats.dash_kills.to_string()),
+        ("Nuke kills", stats.nuke_kills.to_string()),
+        ("Max combo", stats.max_combo.to_string()),
+        ("Dashes fired", stats.dashes_fired.to_string()),
+        ("Weak-spot hits", stats.weak_spot_hits.to_string()),
+        ("Weak-spot accuracy", accuracy),
+        ("Damage taken", stats.damage_taken.to_string()),
+        ("Antennas hacked", stats.antennas_hacked.to_string()),
+    ]
+}
+
+fn label(text: &str, font_size: f32, color: Srgba) -> impl Bundle + use<> {
+    (
+        Text::new(text),
+        TextFont {
+            font_size,
+            ..default()
+        },
+        TextColor(color.into()),
+    )
+}
+
+fn button(kind: GameOverButton) -> impl Bundle {
+    (
+        Button,
+        kind,
         Node {
             justify_content: JustifyContent::Center,
             align_items: AlignItems::Center,
-            display: Display::Flex,
-            flex_direction: FlexDirection::Column,
-            row_gap: Val::Px(16.),
-            padding: UiRect::all(Val::Px(16.)),
-            width: Val::Percent(100.),
-            height: Val::Percent(100.),
+            padding: UiRect::all(Val::Px(8.0)),
             ..default()
         },
-        BackgroundColor(tailwind::GRAY_900.with_alpha(0.8).into()),
-        children![
-            (
-                Text::new("GAME OVER"),
-                TextFont {
-                    font_size: 40.0,
-                    ..default()
-                },
-                TextColor(tailwind::GRAY_200.into()),
-            ),
-            (
-                Text::new(format!("Final Score: {}", score.0)),
-                TextFont {
-                    font_size: 40.0,
-                    ..default()
-                },
-                TextColor(tailwind::GRAY_200.into()),
-            ),
-            (
-                Button,
-                Node {
-                    justify_content: JustifyContent::Center,
-                    align_items: AlignItems::Center,
-                    padding: UiRect::all(Val::Px(8.0)),
-                    margin: UiRect::top(Val::Auto),
-                    ..default()
-                },
-                BackgroundColor(tailwind::BLUE_700.into()),
-                children![(
-                    Text::new("Click to Play Again"),
-                    TextFont {
-                        font_size: 20.0,
-                        ..default()
-                    },
-                    TextColor(tailwind::GRAY_200.into()),
-                )],
-            )
-        ],
-    ));
+        BackgroundColor(tailwind::BLUE_700.into()),
+    )
 }
 
-pub fn restart_on_click(
-    mut interaction_query: Query<&Interaction, (Changed<Interaction>, With<Button>)>,
+pub fn game_over_buttons(
+    mut commands: Commands,
+    interaction_query: Query<(&Interaction, &GameOverButton), Changed<Interaction>>,
+    mut name_entry: Option<ResMut<NameEntry>>,
+    mut high_scores: ResMut<HighScores>,
+    stats: Res<RunStats>,
     mut next_state: ResMut<NextState<GameState>>,
 ) {
-    for interaction in interaction_query.iter_mut() {
-        if *interaction == Interaction::Pressed {
-            next_state.set(GameState::Running);
+    for (interaction, button) in interaction_query.iter() {
+        if *interaction != Interaction::Pressed {
+            continue;
         }
+
+        match (*button, name_entry.as_mut()) {
+            (GameOverButton::PlayAgain, _) => next_state.set(GameState::Running),
+            (GameOverButton::ExportStats, _) => stats.export(),
+            (GameOverButton::Letter(index), Some(entry)) => cycle_letter(&mut entry.0.name, index),
+            (GameOverButton::SaveScore, Some(entry)) => {
+                save_high_score(&mut commands, &entry.0, &mut high_scores);
+            }
+            _ => {}
+        }
+    }
+}
+
+/// Touch and mouse players pick letters by clicking on them.
+fn cycle_letter(name: &mut String, index: usize) {
+    let mut letters: Vec<u8> = name.bytes().collect();
+
+    if index < letters.len() {
+        letters[index] = match letters[index] {
+            b'Z' => b'A',
+            letter => letter + 1,
+        };
+    } else {
+        letters.resize(index + 1, b'A');
     }
+
+    *name = sanitize_name(&String::from_utf8_lossy(&letters));
 }
 
+/// Letters add to the name, Enter saves it.
+pub fn type_name(
+    mut commands: Commands,
+    mut keyboard: EventReader<KeyboardInput>,
+    name_entry: Option<ResMut<NameEntry>>,
+    mut high_scores: ResMut<HighScores>,
+) {
+    let Some(mut entry) = name_entry else {
+        keyboard.clear();
+        return;
+    };
+
+    for event in keyboard.read().filter(|event| event.state.is_pressed()) {
+        match &event.logical_key {
+            Key::Character(c) => {
+                let name = format!("{}{c}", entry.0.name);
+                entry.0.name = sanitize_name(&name);
+            }
+            Key::Backspace => {
+                entry.0.name.pop();
+            }
+            Key::Enter => {
+                save_high_score(&mut commands, &entry.0, &mut high_scores);
+                return;
+            }
+            _ => {}
+        }
+    }
+}
+
+/// Waits for at least one letter before saving.
+fn save_high_score(commands: &mut Commands, entry: &HighScore, high_scores: &mut HighScores) {
+    if entry.name.is_empty() {
+        return;
+    }
+
+    high_scores.insert(entry.clone());
+    high_scores.save();
+    commands.remove_resource::<NameEntry>();
+}
+
+pub fn update_name_entry(
+    mut commands: Commands,
+    name_entry: Option<Res<NameEntry>>,
+    entry_ui: Query<Entity, With<NameEntryUI>>,
+    mut letters: Query<(&LetterText, &mut Text)>,
+) {
+    let Some(entry) = name_entry else {
+        for entity in entry_ui.iter() {
+            commands.entity(entity).despawn();
+        }
+        return;
+    };
+
+    for (letter, mut text) in letters.iter_mut() {
+        text.0 = entry
+            .0
+            .name
+            .chars()
+            .nth(letter.0)
+            .unwrap_or('_')
+            .to_string();
+    }
+}
+
+/// A high score is only kept once its name is confirmed, so leaving the
+/// screen drops a pending one.
 pub fn cleanup_gameover_ui(mut commands: Commands, query: Query<Entity, With<GameOverUI>>) {
+    commands.remove_resource::<NameEntry>();
+
     for entity in query.iter() {
         commands.entity(entity).despawn();
     }
@@ -0,0 +1,185 @@
+use bevy::color::palettes::tailwind;
+use bevy::prelude::*;
+
+use crate::{
+    difficulty::Difficulty,
+    game_state::GameState,
+    high_score::{HighScores, NAME_LENGTH},
+};
+
+#[derive(Component)]
+pub struct HighScoresUI;
+
+#[derive(Component, Clone, Copy)]
+pub enum HighScoresButton {
+    Difficulty,
+    Back,
+}
+
+/// Holds one row per entry of the difficulty being shown.
+#[derive(Component)]
+pub struct HighScoreTable;
+
+/// Which difficulty's table is on screen, starting at the selected one.
+#[derive(Resource)]
+pub struct ShownDifficulty(Difficulty);
+
+pub fn setup_high_scores_ui(mut commands: Commands, difficulty: Res<Difficulty>) {
+    commands.insert_resource(ShownDifficulty(*difficulty));
+
+    commands
+        .spawn((
+            HighScoresUI,
+            Node {
+                justify_content: JustifyContent::Center,
+                align_items: AlignItems::Center,
+                flex_direction: FlexDirection::Column,
+                row_gap: Val::Px(12.),
+                width: Val::Percent(100.),
+                height: Val::Percent(100.),
+                ..default()
+            },
+            BackgroundColor(tailwind::GRAY_900.into()),
+        ))
+        .with_children(|parent| {
+            parent.spawn(label("High Scores", 40.));
+            parent
+                .spawn(button(HighScoresButton::Difficulty))
+                .with_child(label("", 20.));
+            parent.spawn((
+                HighScoreTable,
+                Node {
+                    flex_direction: FlexDirection::Column,
+                    row_gap: Val::Px(4.),
+                    ..default()
+                },
+            ));
+            parent
+                .spawn(Node {
+                    margin: UiRect::top(Val::Px(16.)),
+                    ..default()
+                })
+                .with_children(|row| {
+                    row.spawn(button(HighScoresButton::Back))
+                        .with_child(label("Back", 20.));
+                });
+        });
+}
+
+fn label(text: &str, font_size: f32) -> impl Bundle + use<> {
+    (
+        Text::new(text),
+        TextFont {
+            font_size,
+            ..default()
+        },
+        TextColor(tailwind::GRAY_200.into()),
+    )
+}
+
+fn button(kind: HighScoresButton) -> impl Bundle {
+    (
+        Button,
+        kind,
+        Node {
+            padding: UiRect::all(Val::Px(8.0)),
+            ..default()
+        },
+        BackgroundColor(tailwind::BLUE_700.into()),
+    )
+}
+
+fn cell(text: String, width: f32, color: Srgba) -> impl Bundle {
+    (
+        Text::new(text),
+        TextFont {
+            font_size: 20.,
+            ..default()
+        },
+        TextColor(color.into()),
+        Node {
+            width: Val::Px(width),
+            ..default()
+        },
+    )
+}
+
+pub fn high_scores_buttons(
+    interaction_query: Query<(&Interaction, &HighScoresButton), Changed<Interaction>>,
+    mut shown: ResMut<ShownDifficulty>,
+    mut next_state: ResMut<NextState<GameState>>,
+) {
+    for (interaction, button) in interaction_query.iter() {
+        if *interaction != Interaction::Pressed {
+            continue;
+        }
+
+        match button {
+            HighScoresButton::Difficulty => shown.0 = shown.0.next(),
+            HighScoresButton::Back => next_state.set(GameState::MainMenu),
+        }
+    }
+}
+
+/// Rebuilds the rows whenever another difficulty is picked.
+pub fn update_high_score_table(
+    mut commands: Commands,
+    shown: Res<ShownDifficulty>,
+    high_scores: Res<HighScores>,
+    tables: Query<Entity, With<HighScoreTable>>,
+    buttons: Query<(&HighScoresButton, &Children)>,
+    mut texts: Query<&mut Text>,
+) {
+    for (button, children) in buttons.iter() {
+        if !matches!(button, HighScoresButton::Difficulty) {
+            continue;
+        }
+
+        let mut texts = texts.iter_many_mut(children);
+        while let Some(mut text) = texts.fetch_next() {
+            text.0 = format!("Difficulty: {}", shown.0.label());
+        }
+    }
+
+    for table in tables.iter() {
+        commands
+            .entity(table)
+            .despawn_related::<Children>()
+            .with_children(|table| {
+                let mut entries = high_scores.top(shown.0).enumerate().peekable();
+                if entries.peek().is_none() {
+                    table.spawn(label("No scores yet", 20.));
+                }
+
+                for (rank, entry) in entries {
+                    table
+                        .spawn(Node {
+                            column_gap: Val::Px(16.),
+                            ..default()
+                        })
+                        .with_children(|row| {
+                            let name = format!("{:-<width$}", entry.name, width = NAME_LENGTH);
+
+                            row.spawn(cell(format!("{}.", rank + 1), 32., tailwind::GRAY_400));
+                            row.spawn(cell(name, 48., tailwind::GRAY_200));
+                            row.spawn(cell(entry.score.to_string(), 64., tailwind::GRAY_200));
+                            row.spawn(cell(entry.date.clone(), 120., tailwind::GRAY_400));
+                            // matches the replay file name
+                            row.spawn(cell(
+                                format!("{:016x}", entry.seed),
+                                200.,
+                                tailwind::GRAY_500,
+                            ));
+                        });
+                }
+            });
+    }
+}
+
+pub fn cleanup_high_scores_ui(mut commands: Commands, query: Query<Entity, With<HighScoresUI>>) {
+    commands.remove_resource::<ShownDifficulty>();
+
+    for entity in query.iter() {
+        commands.entity(entity).despawn();
+    }
+}
@@ -1,29 +1,69 @@
 use bevy::color::palettes::tailwind;
 use bevy::prelude::*;
 
-use crate::game_state::GameState;
+use crate::{
+    difficulty::{Difficulty, RejectedConfig},
+    game_state::GameState,
+};
 
 #[derive(Component)]
 pub struct MainMenuUI;
 
-pub fn setup_main_menu(mut commands: Commands) {
+/// Lists the presets and overrides that couldn't be applied.
+#[derive(Component)]
+pub struct RejectedConfigText;
+
+#[derive(Component, Clone, Copy)]
+pub enum MainMenuButton {
+    Start,
+    Difficulty,
+    HighScores,
+    Controls,
+}
+
+pub fn setup_main_menu(
+    mut commands: Commands,
+    difficulty: Res<Difficulty>,
+    rejected: Res<RejectedConfig>,
+) {
     commands.spawn((
         MainMenuUI,
         Node {
             justify_content: JustifyContent::Center,
             align_items: AlignItems::Center,
+            flex_direction: FlexDirection::Column,
+            row_gap: Val::Px(16.),
             width: Val::Percent(100.),
             height: Val::Percent(100.0),
             ..default()
         },
         BackgroundColor(tailwind::GRAY_900.into()),
-        children![start_button()],
+        children![
+            menu_button(MainMenuButton::Start, "Start Game", 40.),
+            menu_button(
+                MainMenuButton::Difficulty,
+                &difficulty_label(*difficulty),
+                24.
+            ),
+            menu_button(MainMenuButton::HighScores, "High Scores", 24.),
+            menu_button(MainMenuButton::Controls, "Controls", 24.),
+            (
+                RejectedConfigText,
+                Text::new(rejected_label(&rejected)),
+                TextFont {
+                    font_size: 16.,
+                    ..default()
+                },
+                TextColor(tailwind::YELLOW_400.into()),
+            ),
+        ],
     ));
 }
 
-fn start_button() -> impl Bundle + use<> {
+fn menu_button(kind: MainMenuButton, label: &str, font_size: f32) -> impl Bundle + use<> {
     (
         Button,
+        kind,
         Node {
             padding: UiRect::all(Val::Px(8.0)),
             justify_content: JustifyContent::Center,
@@ -32,9 +72,9 @@ fn start_button() -> impl Bundle + use<> {
         },
         BackgroundColor(tailwind::BLUE_700.into()),
         children![(
-            Text::new("Start Game"),
+            Text::new(label),
             TextFont {
-                font_size: 40.0,
+                font_size,
                 ..default()
             },
             TextColor(tailwind::GRAY_200.into()),
@@ -42,14 +82,61 @@ fn start_button() -> impl Bundle + use<> {
     )
 }
 
-pub fn start_game_on_click(
-    mut interaction_query: Query<&Interaction, (Changed<Interaction>, With<Button>)>,
+fn difficulty_label(difficulty: Difficulty) -> String {
+    format!("Difficulty: {}", difficulty.label())
+}
+
+fn rejected_label(rejected: &RejectedConfig) -> String {
+    rejected
+        .0
+        .iter()
+        .map(|problem| format!("Not applied: {problem}"))
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+pub fn main_menu_buttons(
+    mut interaction_query: Query<(&Interaction, &MainMenuButton), Changed<Interaction>>,
+    mut difficulty: ResMut<Difficulty>,
     mut next_state: ResMut<NextState<GameState>>,
 ) {
-    for interaction in interaction_query.iter_mut() {
-        if *interaction == Interaction::Pressed {
-            next_state.set(GameState::Running);
+    for (interaction, button) in interaction_query.iter_mut() {
+        if *interaction != Interaction::Pressed {
+            continue;
+        }
+
+        match button {
+            MainMenuButton::Start => next_state.set(GameState::Running),
+            MainMenuButton::Difficulty => *difficulty = difficulty.next(),
+            MainMenuButton::HighScores => next_state.set(GameState::HighScores),
+            MainMenuButton::Controls => next_state.set(GameState::Controls),
+        }
+    }
+}
+
+pub fn update_difficulty_button(
+    difficulty: Res<Difficulty>,
+    buttons: Query<(&MainMenuButton, &Children)>,
+    mut texts: Query<&mut Text>,
+) {
+    for (button, children) in buttons.iter() {
+        if !matches!(button, MainMenuButton::Difficulty) {
+            continue;
         }
+
+        let mut texts = texts.iter_many_mut(children);
+        while let Some(mut text) = texts.fetch_next() {
+            text.0 = difficulty_label(*difficulty);
+        }
+    }
+}
+
+pub fn update_rejected_config_text(
+    rejected: Res<RejectedConfig>,
+    mut texts: Query<&mut Text, With<RejectedConfigText>>,
+) {
+    for mut text in texts.iter_mut() {
+        text.0 = rejected_label(&rejected);
     }
 }
 
@@ -1,26 +1,79 @@
-use bevy::prelude::*;
+use bevy::{prelude::*, ui::UiSystem};
 
+mod controls;
 mod game_over;
 mod health_ui;
+mod high_scores;
 mod main_menu;
 mod score_ui;
+mod touch_ui;
+mod tuning;
 
-use crate::game_state::GameState;
-use game_over::{cleanup_gameover_ui, restart_on_click, setup_gameover_ui};
+use crate::{
+    args::Args,
+    config::Config,
+    difficulty::{Difficulty, RejectedConfig},
+    game_state::GameState,
+};
+use controls::{
+    capture_binding, cleanup_controls_ui, controls_buttons, setup_controls_ui, update_bindings_text,
+};
+use game_over::{
+    cleanup_gameover_ui, game_over_buttons, setup_gameover_ui, type_name, update_name_entry,
+};
 use health_ui::{setup_health_ui, update_health_ui};
-use main_menu::{cleanup_main_menu, setup_main_menu, start_game_on_click};
+use high_scores::{
+    cleanup_high_scores_ui, high_scores_buttons, setup_high_scores_ui, update_high_score_table,
+};
+use main_menu::{
+    cleanup_main_menu, main_menu_buttons, setup_main_menu, update_difficulty_button,
+    update_rejected_config_text,
+};
 use score_ui::{setup_score_ui, update_score_ui};
+use touch_ui::{setup_touch_ui, update_touch_ui};
+use tuning::{
+    capture_pointer, drag_sliders, scroll_tuning_list, setup_tuning_ui, start_editing,
+    toggle_tuning_ui, tuning_buttons, type_value, update_field_labels, update_field_values,
+};
 
 pub struct UIPlugin;
 
 impl Plugin for UIPlugin {
     fn build(&self, app: &mut App) {
-        app.add_systems(OnEnter(GameState::MainMenu), setup_main_menu)
+        app.add_systems(Startup, setup_touch_ui)
+            .add_systems(Update, update_touch_ui)
+            .add_systems(OnEnter(GameState::MainMenu), setup_main_menu)
             .add_systems(
                 Update,
-                start_game_on_click.run_if(in_state(GameState::MainMenu)),
+                (
+                    main_menu_buttons,
+                    update_difficulty_button.run_if(resource_changed::<Difficulty>),
+                    update_rejected_config_text.run_if(resource_changed::<RejectedConfig>),
+                )
+                    .chain()
+                    .run_if(in_state(GameState::MainMenu)),
             )
             .add_systems(OnExit(GameState::MainMenu), cleanup_main_menu)
+            .add_systems(OnEnter(GameState::Controls), setup_controls_ui)
+            .add_systems(
+                Update,
+                (controls_buttons, capture_binding, update_bindings_text)
+                    .chain()
+                    .run_if(in_state(GameState::Controls)),
+            )
+            .add_systems(OnExit(GameState::Controls), cleanup_controls_ui)
+            .add_systems(OnEnter(GameState::HighScores), setup_high_scores_ui)
+            .add_systems(
+                Update,
+                (
+                    high_scores_buttons,
+                    update_high_score_table
+                        .run_if(resource_exists_and_changed::<high_scores::ShownDifficulty>),
+                )
+                    .chain()
+                    .run_if(in_state(GameState::HighScores)),
+            )
+            .add_systems(OnExit(GameState::HighScores), cleanup_high_scores_ui)
             .add_systems(
                 OnEnter(GameState::Running),
                 (setup_score_ui, setup_health_ui),
@@ -32,8 +85,30 @@ impl Plugin for UIPlugin {
             .add_systems(OnEnter(GameState::GameOver), setup_gameover_ui)
             .add_systems(
                 Update,
-                restart_on_click.run_if(in_state(GameState::GameOver)),
+                (game_over_buttons, type_name, update_name_entry)
+                    .chain()
+                    .run_if(in_state(GameState::GameOver)),
             )
             .add_systems(OnExit(GameState::GameOver), cleanup_gameover_ui);
+
+        if app.world().resource::<Args>().debug {
+            app.add_systems(Startup, setup_tuning_ui)
+                .add_systems(PreUpdate, capture_pointer.after(UiSystem::Focus))
+                .add_systems(
+                    Update,
+                    (
+                        toggle_tuning_ui,
+                        scroll_tuning_list,
+                        tuning_buttons,
+                        drag_sliders,
+                        start_editing,
+                        type_value,
+                        update_field_labels,
+                        update_field_values,
+                    )
+                        .chain()
+                        .run_if(resource_exists::<Config>),
+                );
+        }
     }
 }
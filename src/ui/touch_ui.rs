@@ -0,0 +1,95 @@
+use bevy::color::palettes::tailwind;
+use bevy::prelude::*;
+
+use crate::touch::{TouchControls, JOYSTICK_RADIUS};
+
+const KNOB_RADIUS: f32 = 24.;
+
+#[derive(Component)]
+pub struct JoystickBase;
+
+#[derive(Component)]
+pub struct JoystickKnob;
+
+#[derive(Component)]
+pub struct AimMarker;
+
+pub fn setup_touch_ui(mut commands: Commands) {
+    commands.spawn((
+        JoystickBase,
+        circle(JOYSTICK_RADIUS, tailwind::GRAY_200.with_alpha(0.15)),
+    ));
+    commands.spawn((
+        JoystickKnob,
+        circle(KNOB_RADIUS, tailwind::GRAY_200.with_alpha(0.4)),
+    ));
+    commands.spawn((
+        AimMarker,
+        circle(KNOB_RADIUS, tailwind::RED_500.with_alpha(0.4)),
+    ));
+}
+
+fn circle(radius: f32, color: Srgba) -> impl Bundle {
+    (
+        Node {
+            position_type: PositionType::Absolute,
+            width: Val::Px(radius * 2.),
+            height: Val::Px(radius * 2.),
+            ..default()
+        },
+        BorderRadius::MAX,
+        BackgroundColor(color.into()),
+        Visibility::Hidden,
+    )
+}
+
+pub fn update_touch_ui(
+    controls: Res<TouchControls>,
+    mut base: Query<(&mut Node, &mut Visibility), With<JoystickBase>>,
+    mut knob: Query<(&mut Node, &mut Visibility), (With<JoystickKnob>, Without<JoystickBase>)>,
+    mut aim: Query<
+        (&mut Node, &mut Visibility),
+        (
+            With<AimMarker>,
+            Without<JoystickBase>,
+            Without<JoystickKnob>,
+        ),
+    >,
+) {
+    if !controls.enabled {
+        return;
+    }
+
+    let joystick = controls.joystick.map(|drag| {
+        let offset = (drag.current - drag.origin).clamp_length_max(JOYSTICK_RADIUS);
+        (drag.origin, drag.origin + offset)
+    });
+
+    place(
+        &mut base,
+        joystick.map(|(origin, _)| origin),
+        JOYSTICK_RADIUS,
+    );
+    place(&mut knob, joystick.map(|(_, knob)| knob), KNOB_RADIUS);
+    place(&mut aim, controls.aim.map(|drag| drag.current), KNOB_RADIUS);
+}
+
+/// Centers a circle node on `center`, hiding it when there is nothing to show.
+fn place<F: bevy::ecs::query::QueryFilter>(
+    query: &mut Query<(&mut Node, &mut Visibility), F>,
+    center: Option<Vec2>,
+    radius: f32,
+) {
+    let Ok((mut node, mut visibility)) = query.single_mut() else {
+        return;
+    };
+
+    match center {
+        Some(center) => {
+            node.left = Val::Px(center.x - radius);
+            node.top = Val::Px(center.y - radius);
+            *visibility = Visibility::Visible;
+        }
+        None => *visibility = Visibility::Hidden,
+    }
+}
@@ -0,0 +1,572 @@
+use bevy::color::palettes::tailwind;
+use bevy::input::keyboard::{Key, KeyboardInput};
+use bevy::input::mouse::{MouseScrollUnit, MouseWheel};
+use bevy::prelude::*;
+use bevy::reflect::{PartialReflect, Struct};
+use bevy::ui::RelativeCursorPosition;
+
+use crate::{
+    args::Args,
+    config::{set_field, Config, GameConfig, Validate},
+    difficulty::{Difficulty, Presets},
+    input::PointerOverUi,
+};
+
+const TOGGLE_KEY: KeyCode = KeyCode::F1;
+const ROW_HEIGHT: f32 = 20.;
+
+/// Live editor for every [`GameConfig`] field, shown with `--debug`.
+#[derive(Component)]
+pub struct TuningUI;
+
+#[derive(Component)]
+pub struct TuningList;
+
+#[derive(Component, Clone, Copy)]
+pub enum TuningButton {
+    Reset,
+    Save,
+}
+
+#[derive(Component)]
+pub struct FieldLabel(usize);
+
+/// Drag track covering `min..=max` of field `field`.
+#[derive(Component)]
+pub struct FieldSlider {
+    field: usize,
+    min: f64,
+    max: f64,
+}
+
+#[derive(Component)]
+pub struct SliderFill(usize);
+
+/// Click to type a value.
+#[derive(Component)]
+pub struct FieldInput(usize);
+
+#[derive(Component)]
+pub struct FieldInputText(usize);
+
+/// The field being typed into and the text so far.
+#[derive(Resource)]
+pub struct TuningEdit {
+    field: usize,
+    text: String,
+}
+
+pub fn setup_tuning_ui(mut commands: Commands) {
+    let defaults = GameConfig::default();
+
+    commands
+        .spawn((
+            TuningUI,
+            RelativeCursorPosition::default(),
+            Node {
+                position_type: PositionType::Absolute,
+                right: Val::Px(0.),
+                top: Val::Px(0.),
+                width: Val::Px(400.),
+                height: Val::Percent(100.),
+                flex_direction: FlexDirection::Column,
+                padding: UiRect::all(Val::Px(8.)),
+                row_gap: Val::Px(8.),
+                ..default()
+            },
+            BackgroundColor(tailwind::GRAY_900.with_alpha(0.85).into()),
+            GlobalZIndex(10),
+        ))
+        .with_children(|parent| {
+            parent
+                .spawn(Node {
+                    column_gap: Val::Px(8.),
+                    align_items: AlignItems::Center,
+                    ..default()
+                })
+                .with_children(|header| {
+                    header.spawn(label("Tuning (F1)", 16.));
+                    header
+                        .spawn(button(TuningButton::Reset))
+                        .with_child(label("Reset", 12.));
+                    header
+                        .spawn(button(TuningButton::Save))
+                        .with_child(label("Save", 12.));
+                });
+
+            parent
+                .spawn((
+                    TuningList,
+                    RelativeCursorPosition::default(),
+                    Node {
+                        flex_direction: FlexDirection::Column,
+                        flex_grow: 1.,
+                        min_height: Val::Px(0.),
+                        overflow: Overflow::scroll_y(),
+                        ..default()
+                    },
+                ))
+                .with_children(|list| {
+                    for (index, name) in (0..defaults.field_len())
+                        .filter_map(|index| Some((index, defaults.name_at(index)?)))
+                    {
+                        let Some(default_value) = defaults.field_at(index).and_then(value_of)
+                        else {
+                            continue;
+                        };
+
+                        list.spawn(Node {
+                            column_gap: Val::Px(6.),
+                            align_items: AlignItems::Center,
+                            height: Val::Px(ROW_HEIGHT),
+                            flex_shrink: 0.,
+                            ..default()
+                        })
+                        .with_children(|row| {
+                            row.spawn((
+                                FieldLabel(index),
+                                label(name, 12.),
+                                Node {
+                                    width: Val::Px(190.),
+                                    ..default()
+                                },
+                            ));
+                            row.spawn(slider(&defaults, index, default_value))
+                                .with_child((
+                                    SliderFill(index),
+                                    Node {
+                                        height: Val::Percent(100.),
+                                        ..default()
+                                    },
+                                    BackgroundColor(tailwind::BLUE_500.into()),
+                                ));
+                            row.spawn((
+                                Button,
+                                FieldInput(index),
+                                Node {
+                                    width: Val::Px(70.),
+                                    ..default()
+                                },
+                            ))
+                            .with_child((FieldInputText(index), label("", 12.)));
+                        });
+                    }
+                });
+        });
+}
+
+fn label(text: &str, font_size: f32) -> impl Bundle + use<> {
+    (
+        Text::new(text),
+        TextFont {
+            font_size,
+            ..default()
+        },
+        TextColor(tailwind::GRAY_200.into()),
+    )
+}
+
+fn button(kind: TuningButton) -> impl Bundle {
+    (
+        Button,
+        kind,
+        Node {
+            padding: UiRect::axes(Val::Px(8.), Val::Px(2.)),
+            ..default()
+        },
+        BackgroundColor(tailwind::BLUE_700.into()),
+    )
+}
+
+/// The track spans from zero to twice the default, on the default's side. Fields
+/// that must be greater than zero start a step past it instead.
+fn slider(defaults: &GameConfig, field: usize, default_value: f64) -> impl Bundle {
+    let span = if default_value == 0. {
+        1.
+    } else {
+        default_value * 2.
+    };
+
+    let mut at_zero = defaults.clone();
+    if let Some(value) = at_zero.field_at_mut(field) {
+        set_value(value, 0.);
+    }
+    let is_integer = defaults.field_at(field).is_some_and(|value| {
+        value.try_downcast_ref::<u32>().is_some() || value.try_downcast_ref::<u8>().is_some()
+    });
+    let floor = if at_zero.validate().is_empty() {
+        0.
+    } else if is_integer {
+        1.
+    } else {
+        span / 100.
+    };
+
+    (
+        Interaction::default(),
+        RelativeCursorPosition::default(),
+        FieldSlider {
+            field,
+            min: span.min(floor),
+            max: span.max(0.),
+        },
+        Node {
+            width: Val::Px(100.),
+            height: Val::Px(8.),
+            ..default()
+        },
+        BackgroundColor(tailwind::GRAY_700.into()),
+    )
+}
+
+fn value_of(field: &dyn PartialReflect) -> Option<f64> {
+    field
+        .try_downcast_ref::<f32>()
+        .map(|value| *value as f64)
+        .or_else(|| field.try_downcast_ref::<f64>().copied())
+        .or_else(|| field.try_downcast_ref::<u32>().map(|value| *value as f64))
+        .or_else(|| field.try_downcast_ref::<u8>().map(|value| *value as f64))
+}
+
+fn set_value(field: &mut dyn PartialReflect, value: f64) {
+    if let Some(field) = field.try_downcast_mut::<f32>() {
+        *field = value as f32;
+    } else if let Some(field) = field.try_downcast_mut::<f64>() {
+        *field = value;
+    } else if let Some(field) = field.try_downcast_mut::<u32>() {
+        *field = value.round() as u32;
+    } else if let Some(field) = field.try_downcast_mut::<u8>() {
+        *field = value.round() as u8;
+    }
+}
+
+/// Applies `edit` to a copy of the base config and keeps it only if the result
+/// still validates, the same check the loader makes.
+fn edit_base(
+    config: &mut Config,
+    edit: impl FnOnce(&mut GameConfig) -> Result<(), String>,
+) -> Result<(), String> {
+    let mut candidate = config.base.clone();
+    edit(&mut candidate)?;
+    if candidate == config.base {
+        return Ok(());
+    }
+
+    let problems = candidate.validate();
+    if !problems.is_empty() {
+        return Err(problems.join("; "));
+    }
+
+    config.base = candidate;
+    Ok(())
+}
+
+/// Up to three decimals, without the float noise of widening an `f32`.
+fn display(value: f64) -> String {
+    let text = format!("{value:.3}");
+    text.trim_end_matches('0').trim_end_matches('.').to_string()
+}
+
+pub fn toggle_tuning_ui(
+    keys: Res<ButtonInput<KeyCode>>,
+    mut panels: Query<&mut Visibility, With<TuningUI>>,
+) {
+    if !keys.just_pressed(TOGGLE_KEY) {
+        return;
+    }
+
+    for mut visibility in panels.iter_mut() {
+        visibility.toggle_visible_hidden();
+    }
+}
+
+/// Keeps clicks on the panel from also dashing.
+pub fn capture_pointer(
+    panels: Query<(Entity, &Visibility, &RelativeCursorPosition), With<TuningUI>>,
+    children: Query<&Children>,
+    interactions: Query<&Interaction>,
+    mut pointer_over_ui: ResMut<PointerOverUi>,
+) {
+    let over = panels.iter().any(|(panel, visibility, cursor)| {
+        *visibility != Visibility::Hidden
+            && (cursor.mouse_over()
+                || children.iter_descendants(panel).any(|entity| {
+                    interactions
+                        .get(entity)
+                        .is_ok_and(|i| *i != Interaction::None)
+                }))
+    });
+
+    pointer_over_ui.set_if_neq(PointerOverUi(over));
+}
+
+pub fn drag_sliders(
+    sliders: Query<(&Interaction, &RelativeCursorPosition, &FieldSlider)>,
+    mut config: ResMut<Config>,
+    mut rejected: Local<Option<f64>>,
+) {
+    for (interaction, cursor, slider) in sliders.iter() {
+        let Some(position) = cursor
+            .normalized
+            .filter(|_| *interaction == Interaction::Pressed)
+        else {
+            continue;
+        };
+
+        let t = position.x.clamp(0., 1.) as f64;
+        let value = slider.min + (slider.max - slider.min) * t;
+
+        let result = edit_base(&mut config, |base| {
+            if let Some(field) = base.field_at_mut(slider.field) {
+                set_value(field, value);
+            }
+            Ok(())
+        });
+
+        // holding the slider still would repeat the warning every frame
+        match result {
+            Err(err) if *rejected != Some(value) => {
+                log::warn!("Ignoring slider value {}: {err}", display(value));
+                *rejected = Some(value);
+            }
+            Err(_) => {}
+            Ok(()) => *rejected = None,
+        }
+    }
+}
+
+pub fn start_editing(
+    mut commands: Commands,
+    inputs: Query<(&Interaction, &FieldInput), Changed<Interaction>>,
+    config: Res<Config>,
+) {
+    for (interaction, input) in inputs.iter() {
+        if *interaction != Interaction::Pressed {
+            continue;
+        }
+
+        let text = config
+            .base
+            .field_at(input.0)
+            .and_then(value_of)
+            .map(display)
+            .unwrap_or_default();
+
+        commands.insert_resource(TuningEdit {
+            field: input.0,
+            text,
+        });
+    }
+}
+
+/// Enter applies the typed value, Escape drops it.
+pub fn type_value(
+    mut commands: Commands,
+    mut keyboard: EventReader<KeyboardInput>,
+    edit: Option<ResMut<TuningEdit>>,
+    mut config: ResMut<Config>,
+) {
+    let Some(mut edit) = edit else {
+        keyboard.clear();
+        return;
+    };
+
+    for event in keyboard.read().filter(|event| event.state.is_pressed()) {
+        match &event.logical_key {
+            Key::Character(c) if c.chars().all(|c| c.is_ascii_digit() || ".-e".contains(c)) => {
+                edit.text.push_str(c);
+            }
+            Key::Backspace => {
+                edit.text.pop();
+            }
+            Key::Escape => {
+                commands.remove_resource::<TuningEdit>();
+                return;
+            }
+            Key::Enter => {
+                let name = config
+                    .base
+                    .name_at(edit.field)
+                    .unwrap_or_default()
+                    .to_string();
+
+                let text = edit.text.clone();
+                if let Err(err) = edit_base(&mut config, |base| set_field(base, &name, &text)) {
+                    log::warn!("{err}");
+                }
+                commands.remove_resource::<TuningEdit>();
+                return;
+            }
+            _ => {}
+        }
+    }
+}
+
+pub fn tuning_buttons(
+    interaction_query: Query<(&Interaction, &TuningButton), Changed<Interaction>>,
+    mut config: ResMut<Config>,
+    args: Res<Args>,
+) {
+    for (interaction, button) in interaction_query.iter() {
+        if *interaction != Interaction::Pressed {
+            continue;
+        }
+
+        match button {
+            TuningButton::Reset => config.base = GameConfig::default(),
+            TuningButton::Save => save_config(&config.base, &args.config_file()),
+        }
+    }
+}
+
+/// Writes back to the config file the game was started with. Comments in RON
+/// files are lost.
+#[cfg(not(target_arch = "wasm32"))]
+fn save_config(config: &GameConfig, path: &std::path::Path) {
+    use crate::config::Format;
+
+    let result = Format::from_path(path)
+        .ok_or_else(|| std::io::Error::other("not a .json or .ron file"))
+        .and_then(|format| format.serialize(config))
+        .and_then(|contents| std::fs::write(path, contents));
+
+    match result {
+        Ok(()) => log::info!("Saved config to {}", path.display()),
+        Err(err) => log::warn!("Failed to save config to {}: {err}", path.display()),
+    }
+}
+
+#[cfg(target_arch = "wasm32")]
+fn save_config(_config: &GameConfig, _path: &std::path::Path) {
+    log::warn!("Saving the config isn't supported on the web");
+}
+
+pub fn scroll_tuning_list(
+    mut wheel: EventReader<MouseWheel>,
+    mut lists: Query<
+        (&mut ScrollPosition, &ComputedNode, &RelativeCursorPosition),
+        With<TuningList>,
+    >,
+) {
+    let delta: f32 = wheel
+        .read()
+        .map(|event| match event.unit {
+            MouseScrollUnit::Line => event.y * ROW_HEIGHT,
+            MouseScrollUnit::Pixel => event.y,
+        })
+        .sum();
+
+    if delta == 0. {
+        return;
+    }
+
+    for (mut scroll, node, cursor) in lists.iter_mut() {
+        if !cursor.mouse_over() {
+            continue;
+        }
+
+        let max_offset =
+            (node.content_size().y - node.size().y).max(0.) * node.inverse_scale_factor();
+        scroll.offset_y = (scroll.offset_y - delta).clamp(0., max_offset);
+    }
+}
+
+/// Dims fields the preset or `--set` decide, since editing them has no effect.
+pub fn update_field_labels(
+    config: Res<Config>,
+    presets: Option<Res<Presets>>,
+    difficulty: Res<Difficulty>,
+    args: Res<Args>,
+    mut labels: Query<(&FieldLabel, &mut TextColor)>,
+) {
+    for (label, mut color) in labels.iter_mut() {
+        let name = config.game.name_at(label.0).unwrap_or_default();
+
+        let preset = presets
+            .as_ref()
+            .and_then(|presets| presets.presets.get(*difficulty))
+            .is_some_and(|preset| preset.sets(name));
+        let overridden = args.overrides.iter().any(|o| o.key == name);
+
+        color.0 = if preset || overridden {
+            tailwind::GRAY_500.into()
+        } else {
+            tailwind::GRAY_200.into()
+        };
+    }
+}
+
+/// Shows the effective values, with the difficulty and overrides applied.
+pub fn update_field_values(
+    config: Res<Config>,
+    edit: Option<Res<TuningEdit>>,
+    mut fills: Query<(&SliderFill, &mut Node)>,
+    sliders: Query<&FieldSlider>,
+    mut texts: Query<(&FieldInputText, &mut Text)>,
+) {
+    let value = |field: usize| config.game.field_at(field).and_then(value_of);
+
+    for (fill, mut node) in fills.iter_mut() {
+        let Some(slider) = sliders.iter().find(|slider| slider.field == fill.0) else {
+            continue;
+        };
+
+        let t = value(fill.0).map_or(0., |value| {
+            ((value - slider.min) / (slider.max - slider.min)).clamp(0., 1.)
+        });
+        node.width = Val::Percent(t as f32 * 100.);
+    }
+
+    for (input, mut text) in texts.iter_mut() {
+        text.0 = match edit.as_ref().filter(|edit| edit.field == input.0) {
+            Some(edit) => format!("{}_", edit.text),
+            None => value(input.0).map_or_else(String::new, display),
+        };
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::state::app::StatesPlugin;
+
+    use super::*;
+    use crate::{
+        game_state::GameState,
+        input::{Action, ActionMap, ActionState, InputPlugin},
+        replay::{Replay, ReplayMode},
+        touch::TouchControls,
+    };
+
+    #[test]
+    fn pressing_a_slider_does_not_dash() {
+        let mut app = App::new();
+        app.add_plugins((MinimalPlugins, StatesPlugin, InputPlugin))
+            .insert_state(GameState::Running)
+            .insert_resource(ActionMap::default())
+            .insert_resource(ReplayMode::Recording(Replay::default()))
+            .init_resource::<TouchControls>()
+            .init_resource::<ButtonInput<KeyCode>>()
+            .init_resource::<ButtonInput<MouseButton>>()
+            .add_event::<CursorMoved>()
+            .add_systems(PreUpdate, capture_pointer);
+
+        let slider = app.world_mut().spawn(Interaction::None).id();
+        app.world_mut()
+            .spawn((
+                TuningUI,
+                Visibility::Visible,
+                RelativeCursorPosition::default(),
+            ))
+            .add_child(slider);
+
+        app.world_mut()
+            .resource_mut::<ButtonInput<MouseButton>>()
+            .press(MouseButton::Left);
+        app.update();
+        assert!(app.world().resource::<ActionState>().pressed(Action::Dash));
+
+        *app.world_mut().get_mut::<Interaction>(slider).unwrap() = Interaction::Pressed;
+        app.update();
+        assert!(!app.world().resource::<ActionState>().pressed(Action::Dash));
+    }
+}
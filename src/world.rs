@@ -1,5 +1,5 @@
 use bevy::{audio, prelude::*, render::camera::ScalingMode};
-use bevy_framepace::{FramepacePlugin, FramepaceSettings};
+use bevy_framepace::FramepacePlugin;
 
 use crate::{
     asset_loader::{AudioAssets, ImageAssets},
@@ -28,15 +28,7 @@ impl Plugin for WorldPlugin {
     }
 }
 
-fn setup(
-    mut commands: Commands,
-    mut settings: ResMut<FramepaceSettings>,
-    image_assets: Res<ImageAssets>,
-    audio_assets: Res<AudioAssets>,
-) {
-    use bevy_framepace::Limiter;
-    settings.limiter = Limiter::from_framerate(60.0);
-
+fn setup(mut commands: Commands, image_assets: Res<ImageAssets>, audio_assets: Res<AudioAssets>) {
     let cam = Camera2d;
     let projection = Projection::Orthographic(OrthographicProjection {
         scaling_mode: ScalingMode::FixedVertical {